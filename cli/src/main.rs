@@ -1,18 +1,42 @@
 #![doc = include_str!("../README.md")]
 
 use {
-    anyhow::Result,
+    anyhow::{Result, anyhow, bail},
     bbd_lib::{
-        DecodeFn, EncodeFn, decode, decode_bcd, decode_direct, decode_nlbb, decode_nlbt,
-        decode_nrbb, decode_nrbt, encode, encode_bcd, encode_direct, encode_nlbb, encode_nlbt,
-        encode_nrbb, encode_nrbt,
+        Alignment, Armor, ArmorError, BitOrder, Bitmap, BrailleReader, Bundle, DecodeError,
+        DecodeErrorKind, Detection, Diff, Dither, Encoder, Entry, Graymap, Grouping, Hexdump,
+        Layout, PACKED_STYLE, Palette, Policy, Style, StyleError, align, decode_checked,
+        decode_fec, decode_grouped, decode_with_policy, detect_style, encode_checked, encode_fec,
+        encode_grouped, glob_match, is_armored, pack_decimal, parse_markdown, patch_dump,
+        style_header, unpack_decimals, verify, write_dump,
+    },
+    clap::{
+        ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum,
+        parser::ValueSource,
     },
-    clap::Parser,
     clap_cargo::style::CLAP_STYLING,
-    std::io::{Read, Write},
-    std::path::PathBuf,
+    std::fs::File,
+    std::io::{BufRead, BufReader, BufWriter, Cursor, IsTerminal, Read, Write},
+    std::num::NonZeroUsize,
+    std::path::{Component, Path, PathBuf},
+    std::time::{Duration, UNIX_EPOCH},
 };
 
+/// Number of bytes of input used to detect the style
+const SAMPLE: u64 = 65536;
+
+/// Options whose output is not colored by `--color` and `--palette`
+const COLORLESS: [&str; 8] = [
+    "decode",
+    "armor",
+    "checksums",
+    "fec",
+    "reverse",
+    "raster",
+    "image",
+    "numbers",
+];
+
 #[derive(Parser)]
 #[command(
     version,
@@ -20,29 +44,19 @@ use {
 Binary Braille Dump
 
 Encode/decode data to/from Braille Patterns Unicode Block characters
-\
-    ",
-    after_help = "\
----
-
-Notes:
-
-1. Styles:
-    * `bcd`: Binary Coded Decimal of byte values 0-99
-    * `direct`: Direct encoding using the standard Braille dot values
-    * `nlbb`: Most significant nibble (MSN) left column, most significant bit
-      (MSB) bottom row. This is the default style.
-    * `nlbt`: MSN left column, MSB top row
-    * `nrbb`: MSN right column, MSB bottom row
-    * `nrbt`: MSN right column, MSB top row
 \
     ",
     max_term_width = 80,
     styles = CLAP_STYLING,
+    args_conflicts_with_subcommands = true,
 )]
+#[allow(clippy::struct_excessive_bools)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Decode Braille characters to bytes using the given style; ignores
-    /// wrapping
+    /// wrapping; armored input uses the style in its header and is verified
     #[arg(short)]
     decode: bool,
 
@@ -50,45 +64,1338 @@ struct Cli {
     #[arg(
         short,
         value_name = "STYLE",
-        value_parser = ["bcd", "direct", "nlbb", "nlbt", "nrbb", "nrbt"],
-        default_value = "nlbb")
+        value_parser = str::parse::<StyleArg>,
+        default_value_t = StyleArg::Style(Style::default()))
     ]
-    style: String,
+    style: StyleArg,
+
+    /// Wrap to N columns ("bytes") per line; 0: disable wrapping [default:
+    /// 64, or 16 with `-x`]
+    #[arg(short, value_name = "N")]
+    columns: Option<usize>,
+
+    /// Decoding: handling of characters that are not Braille patterns, like
+    /// carriage returns, a byte order mark, spaces or Markdown fences
+    #[arg(
+        long,
+        value_name = "POLICY",
+        value_enum,
+        default_value = "strict",
+        requires = "decode",
+        conflicts_with_all = ["checksums", "fec", "group", "little_endian"],
+    )]
+    policy: PolicyArg,
+
+    /// Markdown output with a backticked file name and a fenced block per
+    /// file; with `-d`, decode each block to a file of its name in the `-o`
+    /// directory
+    #[arg(short)]
+    markdown: bool,
+
+    /// Directory for the files decoded from a Markdown document (`-d -m`);
+    /// file names must be relative paths without `..`
+    #[arg(
+        short,
+        value_name = "DIR",
+        requires_all = ["decode", "markdown"],
+        conflicts_with_all = ["image", "numbers"],
+    )]
+    output: Option<PathBuf>,
+
+    /// Armored output with a header with the style, columns, length, file
+    /// name and SHA-256 hash
+    #[arg(short, conflicts_with = "decode")]
+    armor: bool,
+
+    /// Per-line CRC-16 checksums; when decoding, reports each bad line and
+    /// decodes the rest
+    #[arg(short = 'k', conflicts_with = "armor")]
+    checksums: bool,
+
+    /// Forward error correction: Reed-Solomon parity cells to repair up to N
+    /// wrong cells per 255-cell block (1-127); when decoding, reports the
+    /// number of repaired cells
+    #[arg(
+        short = 'f',
+        value_name = "N",
+        value_parser = clap::value_parser!(u8).range(1..=127),
+        conflicts_with_all = ["armor", "checksums"],
+    )]
+    fec: Option<u8>,
+
+    /// Hexdump layout like `xxd` with offset, hex, Braille and ASCII columns
+    #[arg(short = 'x', conflicts_with_all = ["decode", "armor", "checksums", "fec"])]
+    hexdump: bool,
+
+    /// Hexdump columns
+    #[arg(
+        long,
+        value_name = "FIELD",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "offset,hex,braille,ascii",
+        requires = "hexdump"
+    )]
+    fields: Vec<Field>,
+
+    /// Group N bytes per space-separated group, also in the hexdump layout;
+    /// 0: no grouping
+    #[arg(short, value_name = "N", conflicts_with_all = ["armor", "checksums", "fec"])]
+    group: Option<usize>,
+
+    /// Little-endian: reverse the bytes within each group; default group: 4
+    /// bytes
+    #[arg(short = 'e', conflicts_with_all = ["armor", "checksums", "fec"])]
+    little_endian: bool,
+
+    /// Reverse a hexdump (`-x`) or other offset-annotated dump to bytes at
+    /// their offsets; gaps are zero-filled
+    #[arg(
+        short,
+        conflicts_with_all = ["decode", "markdown", "armor", "checksums", "fec", "hexdump"],
+    )]
+    reverse: bool,
+
+    /// Patch the reversed bytes into FILE in place instead of writing them to
+    /// stdout; gaps are left as they are
+    #[arg(short, value_name = "FILE", requires = "reverse")]
+    patch: Option<PathBuf>,
+
+    /// Raster mode: show the input as a 1-bit-per-pixel bitmap N pixels wide
+    /// with 2x4 pixels per Braille cell
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = [
+            "decode", "armor", "checksums", "fec", "hexdump", "group", "little_endian", "reverse",
+        ],
+    )]
+    raster: Option<NonZeroUsize>,
+
+    /// Raster mode: least significant bit first instead of most significant
+    /// bit first
+    #[arg(long, requires = "raster")]
+    lsb: bool,
+
+    /// Raster mode: each byte is 8 vertical pixels in pages of 8 rows (like
+    /// SSD1306) instead of 8 horizontal pixels
+    #[arg(long, requires = "raster")]
+    pages: bool,
+
+    /// Image mode: show a Netpbm image (PBM, PGM or PPM) as Braille art N
+    /// (`-c`) cells wide; 0: original size; with `-d`, convert Braille art to
+    /// a PBM image
+    #[arg(
+        short,
+        conflicts_with_all = [
+            "armor", "checksums", "fec", "hexdump", "group", "little_endian", "reverse", "raster",
+        ],
+    )]
+    image: bool,
+
+    /// Image mode: conversion to black and white
+    #[arg(
+        long,
+        value_enum,
+        default_value = "floyd-steinberg",
+        requires = "image"
+    )]
+    dither: DitherArg,
+
+    /// Image mode: pixels darker than N (0-255) are dots with `--dither
+    /// threshold`
+    #[arg(long, value_name = "N", default_value_t = 128, requires = "image")]
+    threshold: u8,
+
+    /// Numbers mode: encode whitespace- or newline-separated decimal numbers
+    /// of any length with an optional sign as packed BCD cells (`bcd` cells
+    /// of each 2 digits and a sign); with `-d`, decode to one number per line
+    #[arg(
+        short,
+        conflicts_with_all = [
+            "armor", "checksums", "fec", "hexdump", "group", "little_endian", "reverse", "raster",
+            "image",
+        ],
+    )]
+    numbers: bool,
+
+    /// Color the cells by byte class like `hexyl`: NUL, printable ASCII,
+    /// whitespace, control, high-bit and 0xFF; `auto` colors a terminal
+    /// unless `NO_COLOR` is set; not supported with `-d`, `-a`, `-k`, `-f`,
+    /// `-r`, `-i`, `-n`, `--raster`, or `-g` or `-e` without `-x`
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        default_value = "auto",
+        conflicts_with_all = COLORLESS,
+    )]
+    color: ColorWhen,
+
+    /// Colors as comma-separated `class=color` pairs; classes: null,
+    /// printable, whitespace, control, high, full; colors: default, black,
+    /// red, green, yellow, blue, magenta, cyan, white, bright-*, 0-255 or
+    /// #rrggbb
+    #[arg(
+        long,
+        value_name = "SPEC",
+        value_parser = str::parse::<Palette>,
+        conflicts_with_all = COLORLESS,
+    )]
+    palette: Option<Palette>,
+
+    /// Input file(s); [default: "-" (stdin)]
+    #[arg(value_name = "PATH")]
+    files: Vec<PathBuf>,
+}
+
+/// Subcommand
+#[derive(Subcommand)]
+enum Command {
+    /// Compare two files cell by cell; exits with 1 if they differ and 2 on
+    /// error
+    Diff(DiffArgs),
+
+    /// Bundle the files in a directory into one text with a manifest of
+    /// their paths, sizes, modes, modification times and SHA-256 hashes
+    Pack(PackArgs),
+
+    /// Restore the files in a bundle written by `bbd pack` after verifying
+    /// their sizes and SHA-256 hashes
+    Unpack(UnpackArgs),
+
+    /// Check that Braille dumps are well-formed for the style and wrap width
+    /// and that armors match their headers without writing output; exits
+    /// with 1 if not
+    Verify(VerifyArgs),
+}
+
+/// Diff subcommand arguments
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct DiffArgs {
+    /// Style of the cells and of Braille dump inputs; see `bbd -h`
+    #[arg(short, value_name = "STYLE", default_value_t = Style::default())]
+    style: Style,
+
+    /// Aligned pairs ("bytes") per line
+    #[arg(short, value_name = "N", default_value_t = 16)]
+    columns: usize,
+
+    /// Align with the fewest insertions and deletions (Myers) instead of
+    /// offset by offset
+    #[arg(short = 'M', long)]
+    myers: bool,
+
+    /// Show the files side by side instead of interleaved
+    #[arg(short = 'y', long)]
+    side_by_side: bool,
+
+    /// Show an XOR row with only the changed dots
+    #[arg(short, long)]
+    xor: bool,
+
+    /// Show the lines without differences too
+    #[arg(short, long)]
+    all: bool,
+
+    /// Highlight the differing cells; `auto` highlights on a terminal unless
+    /// `NO_COLOR` is set
+    #[arg(long, value_name = "WHEN", value_enum, default_value = "auto")]
+    color: ColorWhen,
+
+    /// Old file: a binary, a Braille dump or an armored dump; "-" is stdin
+    #[arg(value_name = "OLD")]
+    old: PathBuf,
+
+    /// New file: a binary, a Braille dump or an armored dump; "-" is stdin
+    #[arg(value_name = "NEW")]
+    new: PathBuf,
+}
+
+/// Pack subcommand arguments
+#[derive(Args)]
+struct PackArgs {
+    /// Style of the files' binary representations; see `bbd -h`
+    #[arg(short, value_name = "STYLE", default_value_t = Style::default())]
+    style: Style,
 
     /// Wrap to N columns ("bytes") per line; 0: disable wrapping
-    #[arg(short, value_name = "N", default_value = "64")]
+    #[arg(short, value_name = "N", default_value_t = 64)]
     columns: usize,
 
-    /// Markdown output
-    #[arg(short, conflicts_with = "decode")]
-    markdown: bool,
+    /// Only pack the files whose relative path matches GLOB; `*` and `?` do
+    /// not match `/`, `**` does, and a GLOB without `/` matches the file name
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip the files and directories whose relative path matches GLOB
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Directory; symbolic links, special files and empty directories are
+    /// not packed
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+}
+
+/// Unpack subcommand arguments
+#[derive(Args)]
+struct UnpackArgs {
+    /// Directory to restore the files in
+    #[arg(short, value_name = "DIR", default_value = ".")]
+    output: PathBuf,
+
+    /// Bundle; "-" is stdin
+    #[arg(value_name = "BUNDLE", default_value = "-")]
+    bundle: PathBuf,
+}
+
+/// Verify subcommand arguments
+#[derive(Args)]
+struct VerifyArgs {
+    /// Style; see `bbd -h`; armored inputs use the style in their header
+    #[arg(short, value_name = "STYLE", default_value_t = Style::default())]
+    style: Style,
+
+    /// Wrap width: N columns ("bytes") per line; 0: no wrapping
+    #[arg(short, value_name = "N", default_value_t = 64)]
+    columns: usize,
 
     /// Input file(s); [default: "-" (stdin)]
     #[arg(value_name = "PATH")]
     files: Vec<PathBuf>,
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-
-    let style = cli.style.as_str();
-    let (encode_byte, decode_byte) = match style {
-        "bcd" => (encode_bcd as EncodeFn, decode_bcd as DecodeFn),
-        "direct" => (encode_direct as EncodeFn, decode_direct as DecodeFn),
-        "nlbb" => (encode_nlbb as EncodeFn, decode_nlbb as DecodeFn),
-        "nlbt" => (encode_nlbt as EncodeFn, decode_nlbt as DecodeFn),
-        "nrbb" => (encode_nrbb as EncodeFn, decode_nrbb as DecodeFn),
-        "nrbt" => (encode_nrbt as EncodeFn, decode_nrbt as DecodeFn),
-        _ => unreachable!(),
+/// Hexdump column
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Field {
+    /// Offset of the first byte of the line
+    Offset,
+
+    /// Bytes in hexadecimal
+    Hex,
+
+    /// Binary representation in the given style
+    Braille,
+
+    /// Printable ASCII characters
+    Ascii,
+}
+
+/// When to color the output
+#[derive(Clone, Copy, ValueEnum)]
+enum ColorWhen {
+    /// If stdout is a terminal and `NO_COLOR` is not set
+    Auto,
+
+    /// Always
+    Always,
+
+    /// Never
+    Never,
+}
+
+/// Decode policy for characters that are not Braille patterns
+#[derive(Clone, Copy, ValueEnum)]
+enum PolicyArg {
+    /// Fail with the position of the first one
+    Strict,
+
+    /// Drop them and report how many were dropped
+    Skip,
+
+    /// Drop them and report each one with its position
+    Warn,
+}
+
+impl From<PolicyArg> for Policy {
+    fn from(policy: PolicyArg) -> Policy {
+        match policy {
+            PolicyArg::Strict => Policy::Strict,
+            PolicyArg::Skip => Policy::Skip,
+            PolicyArg::Warn => Policy::Warn,
+        }
+    }
+}
+
+/// Image mode conversion to black and white
+#[derive(Clone, Copy, ValueEnum)]
+enum DitherArg {
+    /// Pixels darker than the threshold are dots
+    Threshold,
+
+    /// Floyd–Steinberg error diffusion
+    FloydSteinberg,
+
+    /// Ordered dithering with a 4x4 Bayer matrix
+    Ordered,
+}
+
+/// Layer on top of the binary representation
+#[derive(Clone, Copy)]
+enum Layer {
+    /// None
+    Plain,
+
+    /// Per-line checksums
+    Checksums,
+
+    /// Forward error correction with the given number of correctable errors per block
+    Fec(u8),
+
+    /// Grouping
+    Grouped(Grouping),
+}
+
+/// Style argument
+#[derive(Clone, Copy)]
+enum StyleArg {
+    /// Detect the style when decoding
+    Auto,
+
+    /// Given style
+    Style(Style),
+}
+
+impl std::str::FromStr for StyleArg {
+    type Err = StyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "auto" {
+            Ok(StyleArg::Auto)
+        } else {
+            Ok(StyleArg::Style(s.parse()?))
+        }
+    }
+}
+
+impl std::fmt::Display for StyleArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StyleArg::Auto => f.write_str("auto"),
+            StyleArg::Style(style) => write!(f, "{style}"),
+        }
+    }
+}
+
+/// Generate the notes for the after help from the descriptions of all styles
+fn notes() -> String {
+    let mut r = String::from("---\n\nNotes:\n\n1. Styles:\n");
+    for style in Style::ALL {
+        let mut description = style.description().to_string();
+        if *style == Style::default() {
+            description.push_str(". This is the default style.");
+        }
+        r.push_str(&wrap(&format!("* `{style}`: {description}"), 4, 6, 80));
+    }
+    r.push_str(&wrap(
+        "* `custom:D0,D1,D2,D3,D4,D5,D6,D7`: User-defined permutation of the Braille \
+        dots given as the dot values (1, 2, 4, ..., 128) or dot numbers (1-8) for each bit from \
+        the least significant bit (D0) to the most significant bit (D7); e.g. \
+        `custom:8,16,32,128,1,2,4,64` and `custom:4,5,6,8,1,2,3,7` are the same as `nlbb`",
+        4,
+        6,
+        80,
+    ));
+    r.push_str(&wrap(
+        "* `auto`: Detect the style when decoding from a `Style: STYLE` header line at the \
+        start of the input or else by scoring the bytes decoded by each built-in style",
+        4,
+        6,
+        80,
+    ));
+    r
+}
+
+/// Word wrap text to the given width with the given first and subsequent line indents
+fn wrap(text: &str, first: usize, rest: usize, width: usize) -> String {
+    let mut r = String::new();
+    let mut line = " ".repeat(first);
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        if !empty && line.len() + 1 + word.len() > width {
+            r.push_str(&line);
+            r.push('\n');
+            line = " ".repeat(rest);
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    r.push_str(&line);
+    r.push('\n');
+    r
+}
+
+/// Whether to color the output
+fn colored(color: ColorWhen) -> bool {
+    match color {
+        ColorWhen::Auto => {
+            std::io::stdout().is_terminal()
+                && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        }
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+    }
+}
+
+/// Palette to color the output with, if any
+fn palette(color: ColorWhen, palette: Option<Palette>) -> Option<Palette> {
+    colored(color).then(|| palette.unwrap_or_default())
+}
+
+/// Open the given file path for reading; `-` is stdin
+fn open(path: &Path) -> Result<Box<dyn Read>> {
+    Ok(if path.as_os_str() == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(File::open(path)?)
+    })
+}
+
+/// Decode the given file path to stdout using the given style or else detect the style; returns
+/// the number of bad lines if checked
+fn decode(path: &Path, style: Option<Style>, layer: Layer, policy: Policy) -> Result<usize> {
+    let mut input = open(path)?;
+    let mut sample = vec![];
+    (&mut input).take(SAMPLE).read_to_end(&mut sample)?;
+    let text = match std::str::from_utf8(&sample) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&sample[..e.valid_up_to()]).unwrap(),
     };
 
-    let mut files = cli.files.clone();
+    if is_armored(text) {
+        dearmor(path, sample, input, policy)?;
+        return Ok(0);
+    }
+
+    let style = if let Some(style) = style {
+        style
+    } else {
+        let detection = detect(&path.display().to_string(), text, policy)?;
+        if detection.header {
+            let (_, body) = style_header(text).unwrap();
+            sample.drain(..text.len() - body.len());
+        }
+        detection.style
+    };
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    let bad_lines = if let Layer::Plain = layer {
+        decode_stream(
+            path,
+            Cursor::new(sample).chain(input),
+            style,
+            policy,
+            &mut stdout,
+        )?;
+        0
+    } else {
+        input.read_to_end(&mut sample)?;
+        let content = String::from_utf8_lossy(&sample);
+        let (decoded, bad_lines) =
+            decode_layer(&path.display().to_string(), &content, style, layer)?;
+        stdout.write_all(&decoded)?;
+        bad_lines
+    };
+    stdout.flush()?;
+    Ok(bad_lines)
+}
+
+/// Decode a plain binary representation from the given input to the given output with the given
+/// policy, reporting dropped characters
+fn decode_stream(
+    path: &Path,
+    input: impl Read,
+    style: Style,
+    policy: Policy,
+    output: &mut impl Write,
+) -> Result<()> {
+    let mut reader = BrailleReader::new(input, style).with_policy(policy);
+    let copied = std::io::copy(&mut reader, output);
+    let name = path.display().to_string();
+    report(&name, &reader.take_warnings(), 0);
+    if let Err(e) = copied {
+        output.flush()?;
+        if let Some(error) = e.get_ref().and_then(|e| e.downcast_ref::<DecodeError>())
+            && error.kind == DecodeErrorKind::NotBraille
+        {
+            bail!(
+                "`{}`: {error} Use `--policy skip` or `--policy warn` to drop it.",
+                path.display(),
+            );
+        }
+        bail!("`{}`: {e}", path.display());
+    }
+    report(&name, &[], reader.dropped());
+    Ok(())
+}
+
+/// Report the characters dropped by the decode policy: each of the given warnings and the given
+/// number of dropped characters unless 0
+fn report(name: &str, warnings: &[DecodeError], dropped: usize) {
+    for warning in warnings {
+        eprintln!(
+            "`{name}`: Dropped {:?} (U+{:04X}) at line {}, column {}",
+            warning.character,
+            u32::from(warning.character),
+            warning.line,
+            warning.column,
+        );
+    }
+    if dropped > 0 {
+        eprintln!("`{name}`: Dropped {dropped} character(s) that are not Braille patterns");
+    }
+}
+
+/// Detect the style of the given content, also without the characters that the policy drops;
+/// warns if the style is ambiguous
+fn detect(name: &str, text: &str, policy: Policy) -> Result<Detection> {
+    let detection = detect_style(text)
+        .or_else(|| {
+            if policy == Policy::Strict {
+                return None;
+            }
+            // Retry without the characters that the policy drops
+            let cells = text
+                .chars()
+                .filter(|c| matches!(c, '\u{2800}'..='\u{28ff}' | '\\' | '\n'))
+                .collect::<String>();
+            detect_style(&cells)
+        })
+        .ok_or_else(|| anyhow!("Unable to detect the style of `{name}`!"))?;
+    if detection.is_ambiguous() {
+        let candidates = detection
+            .candidates()
+            .iter()
+            .map(|style| format!("`{style}`"))
+            .collect::<Vec<_>>();
+        eprintln!(
+            "Style of `{name}` is ambiguous between {}; using `{}`!",
+            candidates.join(", "),
+            detection.style,
+        );
+    }
+    Ok(detection)
+}
+
+/// Decode content with the given layer other than [`Layer::Plain`]; returns the decoded bytes and
+/// the number of bad lines if checked
+fn decode_layer(name: &str, content: &str, style: Style, layer: Layer) -> Result<(Vec<u8>, usize)> {
+    Ok(match layer {
+        Layer::Plain => unreachable!(),
+        Layer::Checksums => {
+            let checked = decode_checked(content, style);
+            for error in &checked.errors {
+                eprintln!("`{name}`: {error}");
+            }
+            let bad_lines = checked.errors.len();
+            (checked.content, bad_lines)
+        }
+        Layer::Fec(errors) => {
+            let corrected =
+                decode_fec(content, style, errors.into()).map_err(|e| anyhow!("`{name}`: {e}"))?;
+            eprintln!(
+                "`{name}`: Repaired {} wrong cell(s) in {} block(s)",
+                corrected.corrections, corrected.blocks,
+            );
+            (corrected.content, 0)
+        }
+        Layer::Grouped(grouping) => (
+            decode_grouped(content, style, grouping.little_endian)
+                .map_err(|e| anyhow!("`{name}`: {e}"))?,
+            0,
+        ),
+    })
+}
+
+/// Decode each section of the Markdown document at the given file path to a file of its name in
+/// the given directory; returns the number of bad lines if checked
+fn unmarkdown(
+    path: &Path,
+    style: Option<Style>,
+    layer: Layer,
+    policy: Policy,
+    dir: &Path,
+) -> Result<usize> {
+    let mut content = String::new();
+    open(path)?.read_to_string(&mut content)?;
+    let sections = parse_markdown(&content).map_err(|e| anyhow!("`{}`: {e}", path.display()))?;
+
+    let mut bad_lines = 0;
+    for section in sections {
+        let name = format!("{}` in `{}", section.name, path.display());
+        let target = output_path(dir, section.name)?;
+        let decoded = if is_armored(section.body) {
+            let (armor, mut decoded, _) = Armor::decode_with_policy(section.body, policy)
+                .map_err(|e| anyhow!("Invalid armor in `{name}`: {e}"))?;
+            for warning in &mut decoded.warnings {
+                warning.line += section.line - 1;
+            }
+            report(&name, &decoded.warnings, decoded.dropped);
+            armor.content
+        } else {
+            let (style, body) = if let Some(style) = style {
+                (style, section.body)
+            } else {
+                let detection = detect(&name, section.body, policy)?;
+                match style_header(section.body) {
+                    Some((_, body)) if detection.header => (detection.style, body),
+                    _ => (detection.style, section.body),
+                }
+            };
+            // One-based line of the document that the body starts at
+            let line = section.line
+                + section.body[..section.body.len() - body.len()]
+                    .matches('\n')
+                    .count();
+            if let Layer::Plain = layer {
+                let decoded = decode_with_policy(body, style, policy).map_err(|mut e| {
+                    e.line += line - 1;
+                    anyhow!("`{name}`: {e}")
+                })?;
+                report(&name, &decoded.warnings, decoded.dropped);
+                decoded.content
+            } else {
+                let (decoded, bad) = decode_layer(&name, body, style, layer)?;
+                bad_lines += bad;
+                decoded
+            }
+        };
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&target, decoded)?;
+    }
+    Ok(bad_lines)
+}
+
+/// Path of the given file name in the given directory; fails unless the name is a relative path
+/// without `..` that does not go through a symbolic link
+fn output_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let mut r = dir.to_path_buf();
+    let mut empty = true;
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => {
+                r.push(part);
+                empty = false;
+                if r.symlink_metadata().is_ok_and(|m| m.is_symlink()) {
+                    bail!(
+                        "File name `{name}` goes through symbolic link `{}`!",
+                        r.display()
+                    );
+                }
+            }
+            Component::CurDir => {}
+            _ => bail!("Unsafe file name `{name}`! Must be a relative path without `..`."),
+        }
+    }
+    if empty {
+        bail!("Empty file name `{name}`!");
+    }
+    Ok(r)
+}
+
+/// Name of the given file path in a Markdown section: the path with `/` separators if it is
+/// relative without `..`, or else its final component, so that `bbd -d -m` can write it back
+fn section_name(path: &Path) -> String {
+    let components = path
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect::<Vec<_>>();
+    if !components.is_empty()
+        && components
+            .iter()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        components
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    } else if let Some(name) = path.file_name() {
+        name.to_string_lossy().into_owned()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// Encode the given file path to stdout with the given layer; plain wrapping continues across
+/// files via the encoder, so only the `last` file ends the line
+fn encode(
+    path: &Path,
+    style: Style,
+    columns: usize,
+    layer: Layer,
+    encoder: &mut Encoder,
+    markdown: bool,
+    last: bool,
+) -> Result<()> {
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    if markdown {
+        write!(stdout, "`{}`:\n\n```\n", section_name(path))?;
+    }
+    if let Layer::Plain = layer {
+        let mut input = BufReader::new(open(path)?);
+        loop {
+            let chunk = input.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            write!(stdout, "{}", encoder.push(chunk))?;
+            let n = chunk.len();
+            input.consume(n);
+        }
+    } else {
+        let mut content = vec![];
+        open(path)?.read_to_end(&mut content)?;
+        let output = match layer {
+            Layer::Plain => unreachable!(),
+            Layer::Checksums => encode_checked(&content, style, columns),
+            Layer::Fec(errors) => encode_fec(&content, style, errors.into(), columns),
+            Layer::Grouped(grouping) => encode_grouped(&content, style, columns, grouping),
+        };
+        write!(stdout, "{output}")?;
+    }
+    if markdown {
+        // Each fenced block starts a new line
+        writeln!(stdout, "{}\n```\n", encoder.finish())?;
+    } else if !matches!(layer, Layer::Plain) {
+        writeln!(stdout)?;
+    } else if last {
+        writeln!(stdout, "{}", encoder.finish())?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Decode the armored content of the given file path to stdout with the given policy after
+/// verifying each armor
+fn dearmor(
+    path: &Path,
+    mut content: Vec<u8>,
+    mut input: Box<dyn Read>,
+    policy: Policy,
+) -> Result<()> {
+    input.read_to_end(&mut content)?;
+    let content = String::from_utf8(content)?;
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    let mut rest = content.as_str();
+    while is_armored(rest) {
+        let (armor, decoded, tail) = Armor::decode_with_policy(rest, policy)
+            .map_err(|e| anyhow!("Invalid armor in `{}`: {e}", path.display()))?;
+        report(
+            &path.display().to_string(),
+            &decoded.warnings,
+            decoded.dropped,
+        );
+        stdout.write_all(&armor.content)?;
+        rest = tail;
+    }
+    if !rest.trim().is_empty() {
+        bail!("Unexpected content after armor in `{}`!", path.display());
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Encode the given file path to stdout as an armor
+fn armor(path: &Path, style: Style, columns: usize, markdown: bool) -> Result<()> {
+    let mut content = vec![];
+    open(path)?.read_to_end(&mut content)?;
+    let mut armor = Armor::new(content, style, columns);
+    if path.as_os_str() != "-"
+        && let Some(filename) = path.file_name()
+    {
+        armor = armor.with_filename(&filename.to_string_lossy());
+    }
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    if markdown {
+        write!(stdout, "`{}`:\n\n```\n{armor}```\n\n", section_name(path))?;
+    } else {
+        write!(stdout, "{armor}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Show the given file path as a bitmap on stdout, least significant bit first if `lsb` and in the
+/// page layout if `pages`
+fn raster(path: &Path, width: usize, lsb: bool, pages: bool, markdown: bool) -> Result<()> {
+    let bit_order = if lsb { BitOrder::Lsb } else { BitOrder::Msb };
+    let layout = if pages { Layout::Pages } else { Layout::Rows };
+    let mut content = vec![];
+    open(path)?.read_to_end(&mut content)?;
+    let bitmap = Bitmap::from_bytes(&content, width, bit_order, layout);
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    if markdown {
+        write!(stdout, "`{}`:\n\n```\n{bitmap}```\n\n", section_name(path))?;
+    } else {
+        write!(stdout, "{bitmap}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Show the Netpbm image at the given file path as Braille art on stdout, resized to the given
+/// number of cells per line unless 0
+fn image(path: &Path, columns: usize, dither: Dither, markdown: bool) -> Result<()> {
+    let mut content = vec![];
+    open(path)?.read_to_end(&mut content)?;
+    let mut image =
+        Graymap::from_netpbm(&content).map_err(|e| anyhow!("`{}`: {e}", path.display()))?;
+    if columns > 0 {
+        image = image.resize(2 * columns);
+    }
+    let bitmap = image.dither(dither);
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    if markdown {
+        write!(stdout, "`{}`:\n\n```\n{bitmap}```\n\n", section_name(path))?;
+    } else {
+        write!(stdout, "{bitmap}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Convert the Braille art at the given file path to a PBM image on stdout with the given policy
+fn unimage(path: &Path, policy: Policy) -> Result<()> {
+    let mut content = String::new();
+    open(path)?.read_to_string(&mut content)?;
+    if policy != Policy::Strict {
+        let decoded = decode_with_policy(&content, Style::Direct, policy)?;
+        report(
+            &path.display().to_string(),
+            &decoded.warnings,
+            decoded.dropped,
+        );
+        content.retain(|c| matches!(c, '\u{2800}'..='\u{28ff}' | '\n'));
+    }
+    let bitmap =
+        Bitmap::from_braille(&content).map_err(|e| anyhow!("`{}`: {e}", path.display()))?;
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(&bitmap.to_pbm())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Encode the decimal numbers in the given file path to stdout as packed BCD cells
+fn numbers(path: &Path, columns: usize, markdown: bool) -> Result<()> {
+    let mut content = String::new();
+    open(path)?.read_to_string(&mut content)?;
+    let mut encoder = Encoder::new(PACKED_STYLE).with_columns(columns);
+    let mut output = String::new();
+    for number in content.split_whitespace() {
+        let packed = pack_decimal(number)
+            .map_err(|e| anyhow!("Invalid number `{number}` in `{}`: {e}", path.display()))?;
+        output.push_str(&encoder.push(&packed));
+    }
+    output.push_str(&encoder.finish());
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    if markdown {
+        write!(
+            stdout,
+            "`{}`:\n\n```\n{output}\n```\n\n",
+            section_name(path)
+        )?;
+    } else {
+        writeln!(stdout, "{output}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Decode the packed BCD cells in the given file path to stdout as one decimal number per line
+/// with the given policy
+fn unnumbers(path: &Path, policy: Policy) -> Result<()> {
+    let mut content = String::new();
+    open(path)?.read_to_string(&mut content)?;
+    let decoded = decode_with_policy(&content, PACKED_STYLE, policy)
+        .map_err(|e| anyhow!("`{}`: {e}", path.display()))?;
+    report(
+        &path.display().to_string(),
+        &decoded.warnings,
+        decoded.dropped,
+    );
+    let numbers =
+        unpack_decimals(&decoded.content).map_err(|e| anyhow!("`{}`: {e}", path.display()))?;
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    for number in numbers {
+        writeln!(stdout, "{number}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Encode the given file path to stdout in the hexdump layout
+fn hexdump(path: &Path, hexdump: &Hexdump, markdown: bool) -> Result<()> {
+    let mut input = open(path)?;
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    if markdown {
+        write!(stdout, "`{}`:\n\n```\n", section_name(path))?;
+    }
+    let mut line = String::new();
+    if hexdump.columns == 0 {
+        let mut content = vec![];
+        input.read_to_end(&mut content)?;
+        hexdump.write(&content, &mut line)?;
+        stdout.write_all(line.as_bytes())?;
+    }
+    let mut buffer = vec![0; hexdump.columns];
+    let mut offset = 0;
+    while !buffer.is_empty() {
+        let mut n = 0;
+        while n < buffer.len() {
+            match input.read(&mut buffer[n..])? {
+                0 => break,
+                m => n += m,
+            }
+        }
+        if n == 0 {
+            break;
+        }
+        line.clear();
+        hexdump.write_line(offset, &buffer[..n], &mut line)?;
+        stdout.write_all(line.as_bytes())?;
+        offset += n;
+        if n < buffer.len() {
+            break;
+        }
+    }
+    if markdown {
+        writeln!(stdout, "```\n")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Reverse the dump in the given file path to stdout or patch it into the given file
+fn reverse(path: &Path, style: Style, little_endian: bool, target: Option<&Path>) -> Result<()> {
+    let mut content = String::new();
+    open(path)?.read_to_string(&mut content)?;
+    if let Some(target) = target {
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(target)?;
+        patch_dump(&content, style, little_endian, &mut file)
+            .map_err(|e| anyhow!("Invalid dump `{}`: {e}", path.display()))?;
+    } else {
+        let mut stdout = BufWriter::new(std::io::stdout().lock());
+        write_dump(&content, style, little_endian, &mut stdout)
+            .map_err(|e| anyhow!("Invalid dump `{}`: {e}", path.display()))?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// Read the given file path for diffing: an armored dump is verified and decoded, a Braille dump
+/// is decoded using the given style, and anything else is used as is
+fn load(path: &Path, style: Style) -> Result<Vec<u8>> {
+    let mut content = vec![];
+    open(path)?.read_to_end(&mut content)?;
+    let Ok(text) = std::str::from_utf8(&content) else {
+        return Ok(content);
+    };
+    if is_armored(text) {
+        let (armor, _rest) = Armor::decode(text)
+            .map_err(|e| anyhow!("Invalid armor in `{}`: {e}", path.display()))?;
+        return Ok(armor.content);
+    }
+    let braille = |c: char| ('\u{2800}'..='\u{28ff}').contains(&c);
+    if text.chars().any(braille)
+        && text
+            .chars()
+            .all(|c| braille(c) || c == '\\' || c.is_whitespace())
+    {
+        return decode_grouped(text, style, false)
+            .map_err(|e| anyhow!("Invalid Braille dump `{}`: {e}", path.display()));
+    }
+    Ok(content)
+}
+
+/// Diff two files to stdout; returns whether they differ
+fn diff(args: &DiffArgs) -> Result<bool> {
+    let old = load(&args.old, args.style)?;
+    let new = load(&args.new, args.style)?;
+    for (path, content) in [(&args.old, &old), (&args.new, &new)] {
+        if args.style == Style::Bcd && content.iter().any(|b| *b > 99) {
+            bail!(
+                "Style `bcd` cannot encode `{}`: it has bytes greater than 99!",
+                path.display()
+            );
+        }
+    }
+    let alignment = if args.myers {
+        Alignment::Myers
+    } else {
+        Alignment::Offset
+    };
+    let aligned = align(&old, &new, alignment);
+    let layout = Diff {
+        columns: args.columns,
+        side_by_side: args.side_by_side,
+        xor: args.xor,
+        all: args.all,
+        highlight: colored(args.color),
+        ..Diff::new(args.style)
+    };
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(layout.format(&aligned).as_bytes())?;
+    stdout.flush()?;
+    Ok(aligned.iter().any(|pair| !pair.is_equal()))
+}
+
+/// Add the files under the given directory, whose relative path is the given prefix, in sorted
+/// order to the given entries unless excluded or not included
+fn walk(dir: &Path, prefix: &str, args: &PackArgs, entries: &mut Vec<Entry>) -> Result<()> {
+    let mut children = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    children.sort_by_key(std::fs::DirEntry::file_name);
+    for child in children {
+        let name = child.file_name();
+        let Some(name) = name.to_str() else {
+            bail!("File name `{}` is not UTF-8!", child.path().display());
+        };
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}/{name}")
+        };
+        if args.exclude.iter().any(|glob| glob_match(glob, &path)) {
+            continue;
+        }
+        let metadata = child.path().symlink_metadata()?;
+        if metadata.is_dir() {
+            walk(&child.path(), &path, args, entries)?;
+        } else if !metadata.is_file() {
+            eprintln!(
+                "Skipping `{}`: not a file or directory!",
+                child.path().display()
+            );
+        } else if args.include.is_empty() || args.include.iter().any(|glob| glob_match(glob, &path))
+        {
+            let content = std::fs::read(child.path())?;
+            if args.style == Style::Bcd && content.iter().any(|b| *b > 99) {
+                bail!("Style `bcd` cannot encode `{path}`: it has bytes greater than 99!");
+            }
+            let entry = Entry::new(&path, content)
+                .with_mode(mode(&metadata))
+                .with_mtime(mtime(&metadata)?);
+            if !entry.has_valid_path() {
+                bail!("Unsupported file name {path:?}!");
+            }
+            entries.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Permission bits of a file
+#[cfg(unix)]
+fn mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+/// Permission bits of a file
+#[cfg(not(unix))]
+fn mode(metadata: &std::fs::Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+/// Modification time of a file in seconds since the Unix epoch
+fn mtime(metadata: &std::fs::Metadata) -> Result<i64> {
+    Ok(match metadata.modified()?.duration_since(UNIX_EPOCH) {
+        Ok(after) => i64::try_from(after.as_secs())?,
+        Err(before) => -i64::try_from(before.duration().as_secs())?,
+    })
+}
+
+/// Pack the files in a directory to stdout as a bundle
+fn pack(args: &PackArgs) -> Result<()> {
+    if !args.dir.is_dir() {
+        bail!("Directory `{}` does not exist!", args.dir.display());
+    }
+    let mut bundle = Bundle::new(args.style, args.columns);
+    walk(&args.dir, "", args, &mut bundle.entries)?;
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    write!(stdout, "{bundle}")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Restore the files in a bundle with their modes and modification times
+fn unpack(args: &UnpackArgs) -> Result<()> {
+    let mut content = String::new();
+    open(&args.bundle)?.read_to_string(&mut content)?;
+    let (bundle, rest) = Bundle::decode(&content)
+        .map_err(|e| anyhow!("Invalid bundle `{}`: {e}", args.bundle.display()))?;
+    if !rest.trim().is_empty() {
+        bail!(
+            "Unexpected content after bundle in `{}`!",
+            args.bundle.display()
+        );
+    }
+    for entry in &bundle.entries {
+        let target = output_path(&args.output, &entry.path)?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&target)?;
+        file.write_all(&entry.content)?;
+        let modified = if entry.mtime < 0 {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(entry.mtime.unsigned_abs()))
+        } else {
+            UNIX_EPOCH.checked_add(Duration::from_secs(entry.mtime.unsigned_abs()))
+        };
+        if let Some(modified) = modified {
+            file.set_modified(modified)?;
+        }
+        set_mode(&target, entry.mode)?;
+    }
+    Ok(())
+}
+
+/// Set the permission bits of a file
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+/// Set the permission bits of a file: read-only unless writable by the owner
+#[cfg(not(unix))]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    permissions.set_readonly(mode & 0o200 == 0);
+    std::fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+/// Verify the given file path, reporting each problem; returns the number of problems
+fn verify_file(path: &Path, style: Style, columns: usize) -> Result<usize> {
+    let mut content = vec![];
+    open(path)?.read_to_end(&mut content)?;
+    let text = match String::from_utf8(content) {
+        Ok(text) => text,
+        Err(e) => {
+            let index = e.utf8_error().valid_up_to();
+            eprintln!("`{}`: Invalid UTF-8 at byte {index}!", path.display());
+            return Ok(1);
+        }
+    };
+
+    let name = path.display().to_string();
+    if let Ok(sections) = parse_markdown(&text) {
+        let mut problems = 0;
+        for section in sections {
+            let name = format!("{}` in `{name}", section.name);
+            problems += verify_text(&name, section.body, style, columns, section.line - 1);
+        }
+        return Ok(problems);
+    }
+    Ok(verify_text(&name, &text, style, columns, 0))
+}
+
+/// Verify the given text, which starts after the given number of lines of its file, reporting each
+/// problem; returns the number of problems
+fn verify_text(name: &str, text: &str, style: Style, columns: usize, lines: usize) -> usize {
+    if !is_armored(text) {
+        let errors = verify(text, style, columns);
+        for mut error in errors.iter().copied() {
+            error.line += lines;
+            eprintln!("`{name}`: {error}");
+        }
+        return errors.len();
+    }
+    let mut rest = text;
+    while is_armored(rest) {
+        // Line numbers of the armor are relative to its `BEGIN` marker line
+        let offset = lines
+            + text[..text.len() - rest.trim_start().len()]
+                .matches('\n')
+                .count();
+        match Armor::decode(rest) {
+            Ok((_, tail)) => rest = tail,
+            Err(mut e) => {
+                match &mut e {
+                    ArmorError::InvalidHeader { line }
+                    | ArmorError::InvalidValue { line }
+                    | ArmorError::ColumnsMismatch { line } => {
+                        *line += offset;
+                    }
+                    ArmorError::Decode(error) => error.line += offset,
+                    _ => {}
+                }
+                eprintln!("`{name}`: Invalid armor: {e}");
+                return 1;
+            }
+        }
+    }
+    if !rest.trim().is_empty() {
+        eprintln!("`{name}`: Unexpected content after armor!");
+        return 1;
+    }
+    0
+}
+
+/// Verify the given file paths; fails if any has a problem
+fn verify_files(args: &VerifyArgs) -> Result<()> {
+    let mut files = args.files.clone();
     let stdin = PathBuf::from("-");
     if files.is_empty() {
         files.push(stdin.clone());
     }
-    for i in &files {
-        if *i != stdin {
+    check_files(&files, &stdin);
+    let mut problems = 0;
+    for path in &files {
+        problems += verify_file(path, args.style, args.columns)?;
+    }
+    if problems > 0 {
+        bail!("Found {problems} problem(s)!");
+    }
+    Ok(())
+}
+
+/// Run a subcommand
+fn run(command: &Command) -> Result<()> {
+    match command {
+        Command::Diff(args) => match diff(args) {
+            Ok(false) => Ok(()),
+            Ok(true) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {e:?}");
+                std::process::exit(2);
+            }
+        },
+        Command::Pack(args) => pack(args),
+        Command::Unpack(args) => unpack(args),
+        Command::Verify(args) => verify_files(args),
+    }
+}
+
+/// Exit if a file path other than stdin does not exist or is not a file
+fn check_files(files: &[PathBuf], stdin: &Path) {
+    for i in files {
+        if i != stdin {
             if !i.exists() {
                 eprintln!("File path `{}` does not exist!", i.display());
                 std::process::exit(1);
@@ -98,36 +1405,126 @@ fn main() -> Result<()> {
             }
         }
     }
+}
 
-    let mut prev_content_length = 0;
-    for i in &files {
-        if cli.decode {
-            let content = if i.as_os_str() == "-" {
-                let mut r = String::new();
-                std::io::stdin().read_to_string(&mut r).unwrap();
-                r
-            } else {
-                std::fs::read_to_string(i).unwrap()
-            };
-            let binary = decode(&content, decode_byte);
-            std::io::stdout().write_all(&binary).unwrap();
-        } else {
-            let content = if i.as_os_str() == "-" {
-                let mut r = vec![];
-                std::io::stdin().read_to_end(&mut r).unwrap();
-                r
+/// Fail if coloring is requested for a grouping (`-g` or `-e`) outside the hexdump layout, which
+/// clap cannot express as a conflict
+fn check_color(cli: &Cli, matches: &ArgMatches) -> Result<()> {
+    if (cli.group.is_some() || cli.little_endian)
+        && !cli.hexdump
+        && (cli.palette.is_some()
+            || matches.value_source("color") == Some(ValueSource::CommandLine))
+    {
+        bail!("`--color` and `--palette` require the hexdump layout (`-x`) with `-g` or `-e`!");
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let matches = Cli::command().after_help(notes()).get_matches();
+    let cli = Cli::from_arg_matches(&matches)?;
+    if let Some(command) = &cli.command {
+        return run(command);
+    }
+    check_color(&cli, &matches)?;
+
+    let mut files = cli.files.clone();
+    let stdin = PathBuf::from("-");
+    if files.is_empty() {
+        files.push(stdin.clone());
+    }
+    check_files(&files, &stdin);
+
+    let grouping = (cli.group.is_some() || cli.little_endian).then(|| Grouping {
+        size: cli.group.unwrap_or(4),
+        little_endian: cli.little_endian,
+    });
+    let layer = if cli.checksums {
+        Layer::Checksums
+    } else if let Some(errors) = cli.fec {
+        Layer::Fec(errors)
+    } else if let Some(grouping) = grouping {
+        Layer::Grouped(grouping)
+    } else {
+        Layer::Plain
+    };
+
+    let style = match cli.style {
+        StyleArg::Style(style) => Some(style),
+        StyleArg::Auto if cli.decode && matches!(layer, Layer::Plain) => None,
+        StyleArg::Auto if cli.decode => {
+            bail!("Style `auto` is not supported with `-k`, `-f`, `-g` or `-e`!")
+        }
+        StyleArg::Auto => bail!("Style `auto` is only supported when decoding!"),
+    };
+
+    let columns = cli.columns.unwrap_or(if cli.hexdump { 16 } else { 64 });
+    let dither = match cli.dither {
+        DitherArg::Threshold => Dither::Threshold(cli.threshold),
+        DitherArg::FloydSteinberg => Dither::FloydSteinberg,
+        DitherArg::Ordered => Dither::Ordered,
+    };
+    let policy = Policy::from(cli.policy);
+    let palette = palette(cli.color, cli.palette);
+    let mut encoder = Encoder::new(style.unwrap_or_default()).with_columns(columns);
+    if let Some(palette) = palette {
+        encoder = encoder.with_palette(palette);
+    }
+    if cli.decode && cli.markdown && cli.output.is_none() {
+        bail!("Decoding Markdown (`-d -m`) requires an output directory (`-o DIR`)!");
+    }
+    let mut bad_lines = 0;
+    for (n, i) in files.iter().enumerate() {
+        if cli.image {
+            if cli.decode {
+                unimage(i, policy)?;
             } else {
-                std::fs::read(i)?
-            };
-            let binary = encode(&content, encode_byte, cli.columns, prev_content_length);
-            if cli.markdown {
-                println!("`{}`:\n\n```\n{binary}\n```\n", i.display());
+                image(i, columns, dither, cli.markdown)?;
+            }
+        } else if cli.numbers {
+            if cli.decode {
+                unnumbers(i, policy)?;
             } else {
-                println!("{binary}");
+                numbers(i, columns, cli.markdown)?;
             }
-            prev_content_length = content.len();
+        } else if let Some(dir) = &cli.output {
+            bad_lines += unmarkdown(i, style, layer, policy, dir)?;
+        } else if cli.decode {
+            bad_lines += decode(i, style, layer, policy)?;
+        } else if cli.reverse {
+            reverse(i, style.unwrap(), cli.little_endian, cli.patch.as_deref())?;
+        } else if cli.armor {
+            armor(i, style.unwrap(), columns, cli.markdown)?;
+        } else if let Some(width) = cli.raster {
+            raster(i, width.get(), cli.lsb, cli.pages, cli.markdown)?;
+        } else if cli.hexdump {
+            let hexdump_layout = Hexdump {
+                columns,
+                offset: cli.fields.contains(&Field::Offset),
+                hex: cli.fields.contains(&Field::Hex),
+                braille: cli.fields.contains(&Field::Braille),
+                ascii: cli.fields.contains(&Field::Ascii),
+                grouping,
+                palette,
+                ..Hexdump::new(style.unwrap())
+            };
+            hexdump(i, &hexdump_layout, cli.markdown)?;
+        } else {
+            encode(
+                i,
+                style.unwrap(),
+                columns,
+                layer,
+                &mut encoder,
+                cli.markdown,
+                n + 1 == files.len(),
+            )?;
         }
     }
 
+    if bad_lines > 0 {
+        bail!("Found {bad_lines} bad line(s)!");
+    }
+
     Ok(())
 }
@@ -0,0 +1,457 @@
+use {
+    crate::{DecodeError, Decoded, Policy, Style, VerifyErrorKind, policy::Tolerant, verify},
+    alloc::{
+        format,
+        string::{String, ToString},
+        vec::Vec,
+    },
+    core::fmt::Write,
+    sha2::{Digest, Sha256},
+};
+
+/// First line of an armored binary representation
+pub const ARMOR_BEGIN: &str = "-----BEGIN BRAILLE DUMP-----";
+
+/// Last line of an armored binary representation
+pub const ARMOR_END: &str = "-----END BRAILLE DUMP-----";
+
+/**
+Self-describing "braille armor" framing of a binary representation
+
+Similar to PGP ASCII armor, an armored binary representation is a `BEGIN` marker line, `Key: Value`
+header lines, a blank line, the wrapped binary representation from [`Style::encode`] and an `END`
+marker line:
+
+```text
+-----BEGIN BRAILLE DUMP-----
+Style: nlbb
+Columns: 64
+Length: 6
+Filename: hello.txt
+SHA-256: 66a045b452102c59d840ec097d59d9467e13a3f34f6494e539ffd32c1bb35f18
+
+⢄⠮⢦⢦⢾⢐
+-----END BRAILLE DUMP-----
+```
+
+The `Filename` header is optional and unknown headers are ignored when decoding.
+
+```
+use bbd_lib::*;
+
+let armor = Armor::new(b"Hello\n".to_vec(), Style::Nlbb, 64).with_filename("hello.txt");
+let armored = armor.encode();
+assert!(armored.starts_with("-----BEGIN BRAILLE DUMP-----\nStyle: nlbb\n"));
+assert!(armored.ends_with("\n\n⢄⠮⢦⢦⢾⢐\n-----END BRAILLE DUMP-----\n"));
+
+let (decoded, rest) = Armor::decode(&armored).unwrap();
+assert_eq!(decoded, armor);
+assert_eq!(rest, "");
+
+// The header carries the style
+let armored = Armor::new(b"Hello\n".to_vec(), Style::Nrbt, 0).encode();
+assert_eq!(Armor::decode(&armored).unwrap().0.style, Style::Nrbt);
+
+// CRLF line endings are tolerated
+let crlf = Armor::new(b"Hello\n".to_vec(), Style::Nlbb, 4).encode().replace('\n', "\r\n");
+assert_eq!(Armor::decode(&crlf).unwrap().0.content, b"Hello\n");
+
+// Tampering is detected
+let tampered = armored.replace("Length: 6", "Length: 5");
+assert_eq!(
+    Armor::decode(&tampered),
+    Err(ArmorError::LengthMismatch { expected: 5, actual: 6 }),
+);
+
+// So is rewrapping
+let armored = Armor::new(b"Hello\n".to_vec(), Style::Nlbb, 4).encode();
+let rewrapped = armored.replace("⢄⠮⢦⢦\\\n⢾⢐", "⢄⠮⢦\\\n⢦⢾⢐");
+assert_eq!(Armor::decode(&rewrapped), Err(ArmorError::ColumnsMismatch { line: 7 }));
+```
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Armor {
+    /// Style of the binary representation
+    pub style: Style,
+
+    /// Columns ("bytes") per line of the binary representation; 0: no wrapping
+    pub columns: usize,
+
+    /// Optional original file name
+    pub filename: Option<String>,
+
+    /// Content
+    pub content: Vec<u8>,
+}
+
+impl Armor {
+    /// Create an armor for the given content, style and columns
+    #[must_use]
+    pub fn new(content: Vec<u8>, style: Style, columns: usize) -> Armor {
+        Armor {
+            style,
+            columns,
+            filename: None,
+            content,
+        }
+    }
+
+    /// Set the original file name
+    #[must_use]
+    pub fn with_filename(mut self, filename: &str) -> Armor {
+        self.filename = Some(filename.to_string());
+        self
+    }
+
+    /// SHA-256 hash of the content
+    #[must_use]
+    pub fn sha256(&self) -> [u8; 32] {
+        Sha256::digest(&self.content).into()
+    }
+
+    /**
+    Encode to an armored binary representation
+
+    Control characters in the file name are replaced with `?` so that the header stays on one
+    line.
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+    */
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn encode(&self) -> String {
+        let mut r = format!(
+            "{ARMOR_BEGIN}\nStyle: {}\nColumns: {}\nLength: {}\n",
+            self.style,
+            self.columns,
+            self.content.len(),
+        );
+        if let Some(filename) = &self.filename {
+            let filename = filename
+                .chars()
+                .map(|c| if c.is_control() { '?' } else { c })
+                .collect::<String>();
+            writeln!(r, "Filename: {filename}").unwrap();
+        }
+        writeln!(r, "SHA-256: {}\n", hex(&self.sha256())).unwrap();
+        r.push_str(&self.style.encode(&self.content, self.columns, 0));
+        if !r.ends_with('\n') {
+            r.push('\n');
+        }
+        r.push_str(ARMOR_END);
+        r.push('\n');
+        r
+    }
+
+    /**
+    Decode an armored binary representation and verify its length and SHA-256 hash
+
+    Leading whitespace is skipped and a carriage return at the end of a line is ignored. Returns the
+    armor and the rest of the content after the `END` marker line so that concatenated armors can
+    be decoded in turn.
+
+    # Errors
+
+    Returns an error if the armor is malformed, the body does not decode or is not wrapped to the
+    `Columns` header, or the length or SHA-256 hash of the decoded content does not match its header
+    */
+    pub fn decode(content: &str) -> Result<(Armor, &str), ArmorError> {
+        Armor::decode_with_policy(content, Policy::Strict).map(|(armor, _, rest)| (armor, rest))
+    }
+
+    /**
+    Decode an armored binary representation with the given policy for characters in the body that
+    are not Braille patterns and verify its length and SHA-256 hash; see [`Armor::decode`]
+
+    Also returns the characters dropped by the policy, with their lines counted from the start of
+    the armor. The content of the [`Decoded`] is moved into the armor, so it is empty.
+
+    ```
+    use bbd_lib::*;
+
+    let armored = Armor::new(b"Hello\n".to_vec(), Style::Nlbb, 0).encode().replace("⢦⢾", "⢦ ⢾");
+    assert!(Armor::decode(&armored).is_err());
+
+    let (armor, decoded, _) = Armor::decode_with_policy(&armored, Policy::Warn).unwrap();
+    assert_eq!(armor.content, b"Hello\n");
+    assert_eq!(decoded.dropped, 1);
+    assert_eq!((decoded.warnings[0].line, decoded.warnings[0].column), (7, 5));
+    ```
+
+    # Errors
+
+    Returns an error if the armor is malformed, the body does not decode with the policy or is not
+    wrapped to the `Columns` header, or the length or SHA-256 hash of the decoded content does not
+    match its header
+    */
+    pub fn decode_with_policy(
+        content: &str,
+        policy: Policy,
+    ) -> Result<(Armor, Decoded, &str), ArmorError> {
+        let mut lines = Lines::new(content.trim_start());
+        if lines.next() != Some(ARMOR_BEGIN) {
+            return Err(ArmorError::MissingBegin);
+        }
+
+        let (mut style, mut columns, mut length, mut filename, mut sha256) =
+            (None, None, None, None, None);
+        loop {
+            let Some(line) = lines.next() else {
+                return Err(ArmorError::MissingEnd);
+            };
+            if line.is_empty() {
+                break;
+            }
+            let Some((key, value)) = line.split_once(": ") else {
+                return Err(ArmorError::InvalidHeader { line: lines.number });
+            };
+            let invalid = ArmorError::InvalidValue { line: lines.number };
+            match key.to_ascii_lowercase().as_str() {
+                "style" => style = Some(value.trim().parse::<Style>().map_err(|_| invalid)?),
+                "columns" => columns = Some(value.trim().parse::<usize>().map_err(|_| invalid)?),
+                "length" => length = Some(value.trim().parse::<usize>().map_err(|_| invalid)?),
+                "filename" => filename = Some(value.to_string()),
+                "sha-256" => sha256 = Some(unhex(value.trim()).ok_or(invalid)?),
+                _ => {}
+            }
+        }
+        let style = style.ok_or(ArmorError::MissingHeader("Style"))?;
+        let length = length.ok_or(ArmorError::MissingHeader("Length"))?;
+        let sha256 = sha256.ok_or(ArmorError::MissingHeader("SHA-256"))?;
+
+        let body_line = lines.number + 1;
+        let body_start = lines.offset;
+        let body_end = loop {
+            let offset = lines.offset;
+            match lines.next() {
+                Some(ARMOR_END) => break offset,
+                Some(_) => {}
+                None => return Err(ArmorError::MissingEnd),
+            }
+        };
+        let body = &lines.content[body_start..body_end];
+        let body = body.strip_suffix('\n').unwrap_or(body);
+
+        let mut decoded = decode_body(body, style, policy).map_err(|mut e| {
+            e.line += body_line - 1;
+            ArmorError::Decode(e)
+        })?;
+        for warning in &mut decoded.warnings {
+            warning.line += body_line - 1;
+        }
+        if let Some(columns) = columns {
+            let unwrapped = body.replace("\r\n", "\n");
+            let unwrapped = unwrapped.strip_suffix('\r').unwrap_or(&unwrapped);
+            if let Some(error) = verify(unwrapped, style, columns)
+                .iter()
+                .find(|error| !matches!(error.kind, VerifyErrorKind::Invalid(..)))
+            {
+                return Err(ArmorError::ColumnsMismatch {
+                    line: error.line + body_line - 1,
+                });
+            }
+        }
+        if decoded.content.len() != length {
+            return Err(ArmorError::LengthMismatch {
+                expected: length,
+                actual: decoded.content.len(),
+            });
+        }
+        let armor = Armor {
+            style,
+            columns: columns.unwrap_or(0),
+            filename,
+            content: core::mem::take(&mut decoded.content),
+        };
+        let actual = armor.sha256();
+        if actual != sha256 {
+            return Err(ArmorError::HashMismatch {
+                expected: sha256,
+                actual,
+            });
+        }
+
+        Ok((armor, decoded, &lines.content[lines.offset..]))
+    }
+}
+
+impl core::fmt::Display for Armor {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+/**
+Content starts with an armor `BEGIN` marker line, ignoring leading whitespace
+
+```
+use bbd_lib::*;
+
+assert!(is_armored(&Armor::new(b"Hello\n".to_vec(), Style::Nlbb, 64).encode()));
+assert!(!is_armored("⢄⠮⢦⢦⢾⢐"));
+```
+*/
+#[must_use]
+pub fn is_armored(content: &str) -> bool {
+    Lines::new(content.trim_start()).next() == Some(ARMOR_BEGIN)
+}
+
+/**
+Error returned when decoding an [`Armor`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArmorError {
+    /// Content does not start with the `BEGIN` marker line
+    MissingBegin,
+
+    /// Content does not have the `END` marker line
+    MissingEnd,
+
+    /// Header line (one-based line number) is not `Key: Value`
+    InvalidHeader { line: usize },
+
+    /// Header line (one-based line number) has an invalid value
+    InvalidValue { line: usize },
+
+    /// Required header is missing
+    MissingHeader(&'static str),
+
+    /// Body does not decode
+    Decode(DecodeError),
+
+    /// Body line (one-based line number) is not wrapped to the `Columns` header; see [`verify`]
+    ColumnsMismatch { line: usize },
+
+    /// Length of the decoded content does not match the `Length` header
+    LengthMismatch { expected: usize, actual: usize },
+
+    /// SHA-256 hash of the decoded content does not match the `SHA-256` header
+    HashMismatch {
+        expected: [u8; 32],
+        actual: [u8; 32],
+    },
+}
+
+impl core::fmt::Display for ArmorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ArmorError::MissingBegin => write!(f, "Missing `{ARMOR_BEGIN}` line!"),
+            ArmorError::MissingEnd => write!(f, "Missing `{ARMOR_END}` line!"),
+            ArmorError::InvalidHeader { line } => {
+                write!(
+                    f,
+                    "Invalid armor header at line {line}! Must be `Key: Value`."
+                )
+            }
+            ArmorError::InvalidValue { line } => {
+                write!(f, "Invalid armor header value at line {line}!")
+            }
+            ArmorError::MissingHeader(key) => write!(f, "Missing `{key}` armor header!"),
+            ArmorError::Decode(e) => e.fmt(f),
+            ArmorError::ColumnsMismatch { line } => write!(
+                f,
+                "Armor body line {line} is not wrapped to the `Columns` header!"
+            ),
+            ArmorError::LengthMismatch { expected, actual } => write!(
+                f,
+                "Length mismatch! Expected {expected} bytes but decoded {actual} bytes.",
+            ),
+            ArmorError::HashMismatch { expected, actual } => write!(
+                f,
+                "SHA-256 mismatch! Expected {} but decoded {}.",
+                hex(expected),
+                hex(actual),
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ArmorError {}
+
+impl From<DecodeError> for ArmorError {
+    fn from(e: DecodeError) -> ArmorError {
+        ArmorError::Decode(e)
+    }
+}
+
+/**
+Line iterator that tracks the byte offset of the next line and the one-based number of the last
+line; trailing carriage returns are removed
+*/
+pub(crate) struct Lines<'a> {
+    pub(crate) content: &'a str,
+    pub(crate) offset: usize,
+    pub(crate) number: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(content: &'a str) -> Lines<'a> {
+        Lines {
+            content,
+            offset: 0,
+            number: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = &self.content[self.offset..];
+        if rest.is_empty() {
+            return None;
+        }
+        let line = if let Some(i) = rest.find('\n') {
+            self.offset += i + 1;
+            &rest[..i]
+        } else {
+            self.offset = self.content.len();
+            rest
+        };
+        self.number += 1;
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+}
+
+/**
+Decode the body of a container with the given style and policy, ignoring a carriage return at the
+end of each line like [`Lines`]
+*/
+pub(crate) fn decode_body(
+    body: &str,
+    style: Style,
+    policy: Policy,
+) -> Result<Decoded, DecodeError> {
+    let mut r = Decoded::default();
+    let mut decoder = Tolerant::new(style, policy);
+    for line in body.split_inclusive('\n') {
+        let cells = line.strip_suffix('\n').unwrap_or(line);
+        let cells = cells.strip_suffix('\r').unwrap_or(cells);
+        decoder.decode(cells, &mut r.content, &mut r.dropped, &mut r.warnings)?;
+        decoder.advance(&line[cells.len()..]);
+    }
+    Ok(r)
+}
+
+/// Lowercase hexadecimal representation of bytes
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut r, b| {
+        write!(r, "{b:02x}").unwrap();
+        r
+    })
+}
+
+/// Parse a 64 digit hexadecimal SHA-256 hash
+pub(crate) fn unhex(s: &str) -> Option<[u8; 32]> {
+    if s.len() != 64 || !s.is_ascii() {
+        return None;
+    }
+    let mut r = [0; 32];
+    for (i, b) in r.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(r)
+}
@@ -0,0 +1,489 @@
+use {
+    crate::{
+        DecodeError, Policy, Style,
+        armor::{Lines, decode_body, hex, unhex},
+    },
+    alloc::{
+        format,
+        string::{String, ToString},
+        vec::Vec,
+    },
+    core::fmt::Write,
+    sha2::{Digest, Sha256},
+};
+
+/// First line of a bundle
+pub const BUNDLE_BEGIN: &str = "-----BEGIN BRAILLE BUNDLE-----";
+
+/// Last line of a bundle
+pub const BUNDLE_END: &str = "-----END BRAILLE BUNDLE-----";
+
+/**
+File in a [`Bundle`]
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// Relative path with `/` separators
+    pub path: String,
+
+    /// Permission bits like `0o644`
+    pub mode: u32,
+
+    /// Modification time in seconds since the Unix epoch
+    pub mtime: i64,
+
+    /// Content
+    pub content: Vec<u8>,
+}
+
+impl Entry {
+    /// Create an entry for the given relative path and content with mode `0o644` and mtime 0
+    #[must_use]
+    pub fn new(path: &str, content: Vec<u8>) -> Entry {
+        Entry {
+            path: path.to_string(),
+            mode: 0o644,
+            mtime: 0,
+            content,
+        }
+    }
+
+    /// Set the permission bits
+    #[must_use]
+    pub fn with_mode(mut self, mode: u32) -> Entry {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the modification time
+    #[must_use]
+    pub fn with_mtime(mut self, mtime: i64) -> Entry {
+        self.mtime = mtime;
+        self
+    }
+
+    /// SHA-256 hash of the content
+    #[must_use]
+    pub fn sha256(&self) -> [u8; 32] {
+        Sha256::digest(&self.content).into()
+    }
+
+    /**
+    Path is relative, has no empty, `.` or `..` components and no control characters; bundles with
+    other paths do not decode
+
+    ```
+    use bbd_lib::*;
+
+    assert!(Entry::new("src/main.rs", vec![]).has_valid_path());
+    assert!(!Entry::new("../main.rs", vec![]).has_valid_path());
+    assert!(!Entry::new("/etc/passwd", vec![]).has_valid_path());
+    assert!(!Entry::new("a\nb", vec![]).has_valid_path());
+    ```
+    */
+    #[must_use]
+    pub fn has_valid_path(&self) -> bool {
+        !self.path.chars().any(char::is_control)
+            && self
+                .path
+                .split('/')
+                .all(|part| !part.is_empty() && part != "." && part != "..")
+    }
+}
+
+/**
+Bundle of files as one text: a manifest with the path, size, mode, mtime and SHA-256 hash of each
+file followed by each file's binary representation
+
+Like an [`Armor`](crate::Armor), a bundle is a `BEGIN` marker line, `Key: Value` header lines and a
+blank line. A manifest line per file (octal mode, mtime, size, SHA-256 hash and path) follows, then
+a blank line and the wrapped binary representation of each file, and an `END` marker line:
+
+```text
+-----BEGIN BRAILLE BUNDLE-----
+Style: nlbb
+Columns: 64
+
+0644 1700000000 6 66a045b452102c59d840ec097d59d9467e13a3f34f6494e539ffd32c1bb35f18 hello.txt
+0755 1700000000 0 e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855 bin/empty
+
+⢄⠮⢦⢦⢾⢐
+
+-----END BRAILLE BUNDLE-----
+```
+
+```
+use bbd_lib::*;
+
+let bundle = Bundle::new(Style::Nlbb, 64)
+    .with_entry(Entry::new("hello.txt", b"Hello\n".to_vec()).with_mtime(1700000000))
+    .with_entry(Entry::new("bin/empty", vec![]).with_mode(0o755).with_mtime(1700000000));
+let bundled = bundle.encode();
+assert!(bundled.starts_with("-----BEGIN BRAILLE BUNDLE-----\nStyle: nlbb\nColumns: 64\n\n0644 "));
+assert!(bundled.ends_with(" bin/empty\n\n⢄⠮⢦⢦⢾⢐\n\n-----END BRAILLE BUNDLE-----\n"));
+
+let (decoded, rest) = Bundle::decode(&bundled).unwrap();
+assert_eq!(decoded, bundle);
+assert_eq!(rest, "");
+
+// CRLF line endings are tolerated
+let crlf = Bundle { columns: 4, ..bundle.clone() }.encode().replace('\n', "\r\n");
+assert_eq!(Bundle::decode(&crlf).unwrap().0.entries, bundle.entries);
+
+// Tampering is detected
+let tampered = bundled.replace("⢄⠮⢦⢦⢾⢐", "⢄⠮⢦⢦⢾⢑");
+assert_eq!(Bundle::decode(&tampered), Err(BundleError::HashMismatch { entry: 0 }));
+let tampered = bundled.replace(" bin/empty", " ../empty");
+assert_eq!(Bundle::decode(&tampered), Err(BundleError::InvalidPath { line: 6 }));
+```
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bundle {
+    /// Style of the binary representations
+    pub style: Style,
+
+    /// Columns ("bytes") per line of the binary representations; 0: no wrapping
+    pub columns: usize,
+
+    /// Files in order
+    pub entries: Vec<Entry>,
+}
+
+impl Bundle {
+    /// Create an empty bundle for the given style and columns
+    #[must_use]
+    pub fn new(style: Style, columns: usize) -> Bundle {
+        Bundle {
+            style,
+            columns,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a file
+    #[must_use]
+    pub fn with_entry(mut self, entry: Entry) -> Bundle {
+        self.entries.push(entry);
+        self
+    }
+
+    /**
+    Encode to a bundle
+
+    Control characters in the paths are replaced with `?` so that each manifest line stays on one
+    line.
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the content of a file has a byte greater than 99
+    */
+    #[must_use]
+    pub fn encode(&self) -> String {
+        let mut r = format!(
+            "{BUNDLE_BEGIN}\nStyle: {}\nColumns: {}\n\n",
+            self.style, self.columns,
+        );
+        for entry in &self.entries {
+            let path = entry
+                .path
+                .chars()
+                .map(|c| if c.is_control() { '?' } else { c })
+                .collect::<String>();
+            writeln!(
+                r,
+                "{:04o} {} {} {} {path}",
+                entry.mode,
+                entry.mtime,
+                entry.content.len(),
+                hex(&entry.sha256()),
+            )
+            .unwrap();
+        }
+        for entry in &self.entries {
+            r.push('\n');
+            let body = self.style.encode(&entry.content, self.columns, 0);
+            if !body.is_empty() {
+                r.push_str(&body);
+                r.push('\n');
+            }
+        }
+        r.push_str(BUNDLE_END);
+        r.push('\n');
+        r
+    }
+
+    /**
+    Decode a bundle and verify the size and SHA-256 hash of each file
+
+    Leading whitespace is skipped and a carriage return at the end of a line is ignored. Returns the
+    bundle and the rest of the content after the `END` marker line.
+
+    # Errors
+
+    Returns an error if the bundle is malformed, a path is not valid (see
+    [`Entry::has_valid_path`]), a body does not decode, or the size or SHA-256 hash of a decoded
+    file does not match its manifest line
+    */
+    pub fn decode(content: &str) -> Result<(Bundle, &str), BundleError> {
+        let mut lines = Lines::new(content.trim_start());
+        if lines.next() != Some(BUNDLE_BEGIN) {
+            return Err(BundleError::MissingBegin);
+        }
+
+        let (mut style, mut columns) = (None, None);
+        loop {
+            let Some(line) = lines.next() else {
+                return Err(BundleError::MissingEnd);
+            };
+            if line.is_empty() {
+                break;
+            }
+            let Some((key, value)) = line.split_once(": ") else {
+                return Err(BundleError::InvalidHeader { line: lines.number });
+            };
+            let invalid = BundleError::InvalidValue { line: lines.number };
+            match key.to_ascii_lowercase().as_str() {
+                "style" => style = Some(value.trim().parse::<Style>().map_err(|_| invalid)?),
+                "columns" => columns = Some(value.trim().parse::<usize>().map_err(|_| invalid)?),
+                _ => {}
+            }
+        }
+        let style = style.ok_or(BundleError::MissingHeader("Style"))?;
+
+        let mut manifest = Vec::new();
+        let mut ended = loop {
+            match lines.next() {
+                None => return Err(BundleError::MissingEnd),
+                Some("") => break false,
+                Some(BUNDLE_END) => break true,
+                Some(line) => manifest.push(parse_entry(line, lines.number)?),
+            }
+        };
+
+        let mut entries = Vec::with_capacity(manifest.len());
+        while !ended {
+            let body_line = lines.number + 1;
+            let body_start = lines.offset;
+            let body_end = loop {
+                let offset = lines.offset;
+                match lines.next() {
+                    None => return Err(BundleError::MissingEnd),
+                    Some("") => break offset,
+                    Some(BUNDLE_END) => {
+                        ended = true;
+                        break offset;
+                    }
+                    Some(_) => {}
+                }
+            };
+            let (mut entry, size, sha256) = manifest
+                .get(entries.len())
+                .cloned()
+                .ok_or(BundleError::ExtraBody { line: body_line })?;
+            let body = &lines.content[body_start..body_end];
+            entry.content = decode_body(
+                body.strip_suffix('\n').unwrap_or(body),
+                style,
+                Policy::Strict,
+            )
+            .map(|decoded| decoded.content)
+            .map_err(|mut e| {
+                e.line += body_line - 1;
+                BundleError::Decode(e)
+            })?;
+            let index = entries.len();
+            if entry.content.len() != size {
+                return Err(BundleError::SizeMismatch {
+                    entry: index,
+                    expected: size,
+                    actual: entry.content.len(),
+                });
+            }
+            if entry.sha256() != sha256 {
+                return Err(BundleError::HashMismatch { entry: index });
+            }
+            entries.push(entry);
+        }
+        if entries.len() < manifest.len() {
+            return Err(BundleError::MissingBody {
+                entry: entries.len(),
+            });
+        }
+
+        let bundle = Bundle {
+            style,
+            columns: columns.unwrap_or(0),
+            entries,
+        };
+        Ok((bundle, &lines.content[lines.offset..]))
+    }
+}
+
+impl core::fmt::Display for Bundle {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+/// Parse a manifest line at the given line number into an entry without content, its size and its
+/// SHA-256 hash
+fn parse_entry(line: &str, number: usize) -> Result<(Entry, usize, [u8; 32]), BundleError> {
+    let invalid = BundleError::InvalidEntry { line: number };
+    let mut fields = line.splitn(5, ' ');
+    let mut field = || fields.next().ok_or(invalid);
+    let mode = u32::from_str_radix(field()?, 8).or(Err(invalid))?;
+    let mtime = field()?.parse::<i64>().or(Err(invalid))?;
+    let size = field()?.parse::<usize>().or(Err(invalid))?;
+    let sha256 = unhex(field()?).ok_or(invalid)?;
+    let entry = Entry::new(field()?, Vec::new())
+        .with_mode(mode)
+        .with_mtime(mtime);
+    if !entry.has_valid_path() {
+        return Err(BundleError::InvalidPath { line: number });
+    }
+    Ok((entry, size, sha256))
+}
+
+/// Path matches a glob pattern
+///
+/// `*` matches any characters but `/`, `**` matches any characters including `/` (followed by `/`,
+/// it also matches no directory) and `?` matches one character but `/`. A pattern without a `/` is
+/// matched against the last component of the path, so `*.rs` matches `src/main.rs`.
+///
+/// ```
+/// use bbd_lib::*;
+///
+/// assert!(glob_match("*.rs", "src/main.rs"));
+/// assert!(glob_match("src/*.rs", "src/main.rs"));
+/// assert!(!glob_match("src/*.rs", "src/bin/main.rs"));
+/// assert!(glob_match("src/**/*.rs", "src/bin/main.rs"));
+/// assert!(glob_match("src/**/*.rs", "src/main.rs"));
+/// assert!(glob_match("target", "crate/target"));
+/// assert!(glob_match("?.txt", "a.txt"));
+/// assert!(!glob_match("?.txt", "ab.txt"));
+/// ```
+#[must_use]
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let path = if pattern.contains('/') {
+        path
+    } else {
+        path.rsplit('/').next().unwrap_or(path)
+    };
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let path = path.chars().collect::<Vec<char>>();
+    glob(&pattern, &path)
+}
+
+/// Core of [`glob_match`]
+fn glob(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            glob(rest, path)
+                || (0..path.len()).any(|i| path[i] == '/' && glob(rest, &path[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| glob(rest, &path[i..])),
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|i| *i == 0 || path[i - 1] != '/')
+            .any(|i| glob(rest, &path[i..])),
+        ['?', rest @ ..] => path.first().is_some_and(|c| *c != '/') && glob(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && glob(rest, &path[1..]),
+    }
+}
+
+/**
+Error returned when decoding a [`Bundle`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BundleError {
+    /// Content does not start with the `BEGIN` marker line
+    MissingBegin,
+
+    /// Content does not have the `END` marker line
+    MissingEnd,
+
+    /// Header line (one-based line number) is not `Key: Value`
+    InvalidHeader { line: usize },
+
+    /// Header line (one-based line number) has an invalid value
+    InvalidValue { line: usize },
+
+    /// Required header is missing
+    MissingHeader(&'static str),
+
+    /// Manifest line (one-based line number) is not mode, mtime, size, SHA-256 hash and path
+    InvalidEntry { line: usize },
+
+    /// Manifest line (one-based line number) has an invalid path
+    InvalidPath { line: usize },
+
+    /// File (zero-based index) has no body
+    MissingBody { entry: usize },
+
+    /// Body (one-based line number) has no manifest line
+    ExtraBody { line: usize },
+
+    /// Body does not decode
+    Decode(DecodeError),
+
+    /// Size of a decoded file (zero-based index) does not match its manifest line
+    SizeMismatch {
+        entry: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// SHA-256 hash of a decoded file (zero-based index) does not match its manifest line
+    HashMismatch { entry: usize },
+}
+
+impl core::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            BundleError::MissingBegin => write!(f, "Missing `{BUNDLE_BEGIN}` line!"),
+            BundleError::MissingEnd => write!(f, "Missing `{BUNDLE_END}` line!"),
+            BundleError::InvalidHeader { line } => {
+                write!(
+                    f,
+                    "Invalid bundle header at line {line}! Must be `Key: Value`."
+                )
+            }
+            BundleError::InvalidValue { line } => {
+                write!(f, "Invalid bundle header value at line {line}!")
+            }
+            BundleError::MissingHeader(key) => write!(f, "Missing `{key}` bundle header!"),
+            BundleError::InvalidEntry { line } => write!(
+                f,
+                "Invalid manifest line {line}! Must be `MODE MTIME SIZE SHA-256 PATH`.",
+            ),
+            BundleError::InvalidPath { line } => write!(
+                f,
+                "Invalid path at manifest line {line}! Must be a relative path without `.` or \
+                `..`.",
+            ),
+            BundleError::MissingBody { entry } => write!(f, "Missing body of file {entry}!"),
+            BundleError::ExtraBody { line } => {
+                write!(f, "Body at line {line} has no manifest line!")
+            }
+            BundleError::Decode(e) => e.fmt(f),
+            BundleError::SizeMismatch {
+                entry,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Size mismatch in file {entry}! Expected {expected} bytes but decoded {actual} \
+                bytes.",
+            ),
+            BundleError::HashMismatch { entry } => write!(f, "SHA-256 mismatch in file {entry}!"),
+        }
+    }
+}
+
+impl core::error::Error for BundleError {}
+
+impl From<DecodeError> for BundleError {
+    fn from(e: DecodeError) -> BundleError {
+        BundleError::Decode(e)
+    }
+}
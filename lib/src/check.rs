@@ -0,0 +1,231 @@
+use {
+    crate::{DecodeError, Style, encode_direct, try_decode_direct},
+    alloc::{string::String, vec::Vec},
+};
+
+/// Separator between the cells of a line and its checksum
+pub const CHECKSUM_SEPARATOR: char = ' ';
+
+/// Number of following lines searched for a line whose checksum matches after a mismatch
+const RESYNC: usize = 16;
+
+/**
+Encode bytes to binary representation with a CRC-16 checksum at the end of each line
+
+Each line of `columns` cells (or the whole content if `columns` is 0) is followed by
+[`CHECKSUM_SEPARATOR`] and 2 cells with the big-endian CRC-16 of the line's bytes drawn in the
+[`Style::Direct`] style, then the usual `\` continuation marker and newline. The checksum is seeded
+with the zero-based line index (see [`line_crc`]), so a dropped or reordered line is detected as
+well as a mangled one.
+
+```
+use bbd_lib::*;
+
+let encoded = encode_checked(b"Hello, world!\n", Style::Nlbb, 8);
+assert_eq!(encoded, "⢄⠮⢦⢦⢾⢢⠂⠿ ⠖⡏\\\n⢾⠗⢦⠦⠊⢐ ⡟⢐");
+assert_eq!(decode_checked(&encoded, Style::Nlbb), Checked {
+    content: b"Hello, world!\n".to_vec(),
+    errors: vec![],
+});
+```
+
+# Panics
+
+Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+*/
+#[must_use]
+pub fn encode_checked(content: &[u8], style: Style, columns: usize) -> String {
+    let columns = if columns == 0 {
+        content.len().max(1)
+    } else {
+        columns
+    };
+    let mut r = String::with_capacity(3 * content.len() + content.len() / columns * 12);
+    for (index, line) in content.chunks(columns).enumerate() {
+        if index > 0 {
+            r.push_str("\\\n");
+        }
+        r.push_str(&style.encode(line, 0, 0));
+        r.push(CHECKSUM_SEPARATOR);
+        for b in line_crc(index, line).to_be_bytes() {
+            r.push(encode_direct(b));
+        }
+    }
+    r
+}
+
+/**
+Decode binary representation with a CRC-16 checksum at the end of each line
+
+Every line is verified and decoding continues after a bad line so that all bad lines are reported.
+The bytes of lines with a checksum mismatch are kept in the content (so that the following bytes
+stay at their offsets) but the bytes of lines that fail to decode are not. When a checksum does
+not match, the following line indexes are tried so that dropped lines are reported as missing
+instead of every following line as mismatched.
+
+```
+use bbd_lib::*;
+
+let encoded = encode_checked(b"The quick brown fox jumps over the lazy dog", Style::Nlbb, 8);
+let mut lines = encoded.lines().collect::<Vec<_>>();
+
+// Mangle line 2 and drop line 4
+lines[1] = "⢆⠮⢖⢆⢶⢦⠄⢦ ⠀⠀\\";
+lines.remove(3);
+let checked = decode_checked(&lines.join("\n"), Style::Nlbb);
+assert_eq!(checked.errors, vec![
+    LineError { line: 2, kind: LineErrorKind::Mismatch },
+    LineError { line: 4, kind: LineErrorKind::Missing(1) },
+]);
+assert_eq!(checked.content.len(), 43 - 8);
+```
+*/
+#[must_use]
+pub fn decode_checked(content: &str, style: Style) -> Checked {
+    let mut r = Checked {
+        content: Vec::with_capacity(content.len() / 4),
+        errors: Vec::new(),
+    };
+    let mut index = 0;
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+        let line = line.strip_suffix('\\').unwrap_or(line);
+        if line.is_empty() {
+            continue;
+        }
+        let error = |kind| LineError { line: number, kind };
+        let Some((cells, checksum)) = line.rsplit_once(CHECKSUM_SEPARATOR) else {
+            r.errors.push(error(LineErrorKind::NoChecksum));
+            index += 1;
+            continue;
+        };
+        let Some(checksum) = parse_checksum(checksum) else {
+            r.errors.push(error(LineErrorKind::NoChecksum));
+            index += 1;
+            continue;
+        };
+        let decoded = match style.try_decode(cells) {
+            Ok(decoded) => decoded,
+            Err(mut e) => {
+                e.line = number;
+                r.errors.push(error(LineErrorKind::Decode(e)));
+                index += 1;
+                continue;
+            }
+        };
+        if line_crc(index, &decoded) == checksum {
+            index += 1;
+        } else if let Some(skip) = (1..=RESYNC).find(|n| line_crc(index + n, &decoded) == checksum)
+        {
+            r.errors.push(error(LineErrorKind::Missing(skip)));
+            index += skip + 1;
+        } else {
+            r.errors.push(error(LineErrorKind::Mismatch));
+            index += 1;
+        }
+        r.content.extend(decoded);
+    }
+    r
+}
+
+/**
+CRC-16/CCITT-FALSE checksum of a line's bytes seeded with the zero-based line index
+
+The big-endian 32-bit line index is processed before the bytes.
+
+```
+use bbd_lib::*;
+
+assert_ne!(line_crc(0, b"abc"), line_crc(1, b"abc"));
+```
+*/
+#[must_use]
+pub fn line_crc(index: usize, bytes: &[u8]) -> u16 {
+    #[allow(clippy::cast_possible_truncation)]
+    let index = (index as u32).to_be_bytes();
+    index.iter().chain(bytes).fold(0xFFFF, |mut crc, b| {
+        crc ^= u16::from(*b) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            };
+        }
+        crc
+    })
+}
+
+/// Parse the 2 cell checksum of a line
+fn parse_checksum(s: &str) -> Option<u16> {
+    let mut chars = s.chars();
+    let (Some(hi), Some(lo), None) = (chars.next(), chars.next(), chars.next()) else {
+        return None;
+    };
+    Some(u16::from_be_bytes([
+        try_decode_direct(hi).ok()?,
+        try_decode_direct(lo).ok()?,
+    ]))
+}
+
+/**
+Result of [`decode_checked`]
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checked {
+    /// Decoded bytes
+    pub content: Vec<u8>,
+
+    /// Bad lines in order
+    pub errors: Vec<LineError>,
+}
+
+/**
+Bad line found by [`decode_checked`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LineError {
+    /// One-based line number in the content
+    pub line: usize,
+
+    /// Reason
+    pub kind: LineErrorKind,
+}
+
+impl core::fmt::Display for LineError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.kind)
+    }
+}
+
+impl core::error::Error for LineError {}
+
+/**
+Reason a line is bad
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineErrorKind {
+    /// Checksum does not match the line
+    Mismatch,
+
+    /// Given number of lines before this line are missing
+    Missing(usize),
+
+    /// Line does not end with a separator and a 2 cell checksum
+    NoChecksum,
+
+    /// Line does not decode
+    Decode(DecodeError),
+}
+
+impl core::fmt::Display for LineErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            LineErrorKind::Mismatch => f.write_str("Checksum mismatch!"),
+            LineErrorKind::Missing(1) => f.write_str("1 line is missing before this line!"),
+            LineErrorKind::Missing(n) => write!(f, "{n} lines are missing before this line!"),
+            LineErrorKind::NoChecksum => f.write_str("Missing checksum!"),
+            LineErrorKind::Decode(e) => e.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use core::fmt::{self, Write};
+
+/**
+Class of a byte for coloring; see [`Palette`]
+
+```
+use bbd_lib::*;
+
+assert_eq!(ByteClass::of(0), ByteClass::Null);
+assert_eq!(ByteClass::of(b'A'), ByteClass::Printable);
+assert_eq!(ByteClass::of(b' '), ByteClass::Whitespace);
+assert_eq!(ByteClass::of(0x1b), ByteClass::Control);
+assert_eq!(ByteClass::of(0x80), ByteClass::HighBit);
+assert_eq!(ByteClass::of(0xff), ByteClass::Full);
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteClass {
+    /// 0x00
+    Null,
+
+    /// Printable ASCII other than space (0x21-0x7E)
+    Printable,
+
+    /// ASCII whitespace: space, tab, newline, form feed and carriage return
+    Whitespace,
+
+    /// Other ASCII (control characters)
+    Control,
+
+    /// 0x80-0xFE
+    HighBit,
+
+    /// 0xFF
+    Full,
+}
+
+impl ByteClass {
+    /// All byte classes
+    pub const ALL: &[ByteClass] = &[
+        ByteClass::Null,
+        ByteClass::Printable,
+        ByteClass::Whitespace,
+        ByteClass::Control,
+        ByteClass::HighBit,
+        ByteClass::Full,
+    ];
+
+    /// Class of the given byte
+    #[must_use]
+    pub fn of(b: u8) -> ByteClass {
+        match b {
+            0 => ByteClass::Null,
+            0xFF => ByteClass::Full,
+            b if b.is_ascii_graphic() => ByteClass::Printable,
+            b if b.is_ascii_whitespace() => ByteClass::Whitespace,
+            b if b.is_ascii() => ByteClass::Control,
+            _ => ByteClass::HighBit,
+        }
+    }
+
+    /// Name of the byte class in a palette specification
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            ByteClass::Null => "null",
+            ByteClass::Printable => "printable",
+            ByteClass::Whitespace => "whitespace",
+            ByteClass::Control => "control",
+            ByteClass::HighBit => "high",
+            ByteClass::Full => "full",
+        }
+    }
+
+    /// Index into the palette
+    fn index(self) -> usize {
+        ByteClass::ALL.iter().position(|c| *c == self).unwrap()
+    }
+}
+
+/**
+Terminal color of a byte class; see [`Palette`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// Terminal default; no escape sequence
+    Default,
+
+    /// One of the 16 standard colors (0-7, or 8-15 for the bright variants)
+    Ansi(u8),
+
+    /// One of the 256 indexed colors
+    Fixed(u8),
+
+    /// 24-bit color
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Names of the standard colors; the bright variants are prefixed with `bright-`
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+
+    /// Write the SGR escape sequence that selects the color as the foreground color
+    fn write_sgr(self, out: &mut impl Write) -> fmt::Result {
+        match self {
+            Color::Default => Ok(()),
+            Color::Ansi(n) if n < 8 => write!(out, "\x1b[{}m", 30 + n),
+            Color::Ansi(n) => write!(out, "\x1b[{}m", 90 + (n & 7)),
+            Color::Fixed(n) => write!(out, "\x1b[38;5;{n}m"),
+            Color::Rgb(r, g, b) => write!(out, "\x1b[38;2;{r};{g};{b}m"),
+        }
+    }
+}
+
+impl core::str::FromStr for Color {
+    type Err = PaletteError;
+
+    /**
+    Parse `default`, a standard color name like `red` or `bright-red`, an indexed color (0-255)
+    or a 24-bit color (`#rrggbb`)
+    */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "default" {
+            return Ok(Color::Default);
+        }
+        let (name, bright) = match s.strip_prefix("bright-") {
+            Some(name) => (name, 8),
+            None => (s, 0),
+        };
+        if let Some(n) = Color::NAMES.iter().position(|c| *c == name) {
+            return Ok(Color::Ansi(u8::try_from(n).unwrap() + bright));
+        }
+        if let Some(hex) = s.strip_prefix('#')
+            && hex.len() == 6
+            && hex.is_ascii()
+        {
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+            if let (Ok(r), Ok(g), Ok(b)) = (channel(0), channel(2), channel(4)) {
+                return Ok(Color::Rgb(r, g, b));
+            }
+        }
+        s.parse()
+            .map(Color::Fixed)
+            .or(Err(PaletteError::InvalidColor))
+    }
+}
+
+/**
+Colors of the byte classes for ANSI colored output like `hexyl`
+
+Coloring wraps runs of cells in SGR escape sequences without changing the cells, so colored
+output decodes to the same bytes once the escape sequences are stripped. A palette can be given as
+comma-separated `class=color` pairs (see [`ByteClass::name`] and the [`Color`] syntax) that
+override the defaults.
+
+```
+use bbd_lib::*;
+
+let palette = Palette::default();
+assert_eq!(palette.get(ByteClass::Printable), Color::Ansi(6));
+
+let palette = "printable=bright-blue,null=default,full=#ff8000".parse::<Palette>().unwrap();
+assert_eq!(palette.get(ByteClass::Printable), Color::Ansi(12));
+assert_eq!(palette.get(ByteClass::Null), Color::Default);
+assert_eq!(palette.get(ByteClass::Full), Color::Rgb(255, 128, 0));
+assert_eq!(palette.get(ByteClass::HighBit), Color::Ansi(3));
+
+assert_eq!("bogus=red".parse::<Palette>(), Err(PaletteError::InvalidClass));
+assert_eq!("null=mauve".parse::<Palette>(), Err(PaletteError::InvalidColor));
+
+let mut encoder = Encoder::new(Style::Nlbb).with_palette(Palette::default());
+let colored = encoder.push(b"Hi\n");
+assert_eq!(colored, "\x1b[36m⢄⢎\x1b[0m\x1b[32m⢐\x1b[0m");
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Palette {
+    colors: [Color; 6],
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette {
+            colors: [
+                Color::Ansi(8),
+                Color::Ansi(6),
+                Color::Ansi(2),
+                Color::Ansi(5),
+                Color::Ansi(3),
+                Color::Ansi(1),
+            ],
+        }
+    }
+}
+
+impl Palette {
+    /// Color of the given byte class
+    #[must_use]
+    pub fn get(&self, class: ByteClass) -> Color {
+        self.colors[class.index()]
+    }
+
+    /// Set the color of the given byte class
+    #[must_use]
+    pub fn with(mut self, class: ByteClass, color: Color) -> Palette {
+        self.colors[class.index()] = color;
+        self
+    }
+
+    /**
+    Write bytes in runs of the same color with `write` writing each run; colors are reset after
+    each run
+    */
+    pub(crate) fn write_runs<W: Write>(
+        &self,
+        bytes: &[u8],
+        out: &mut W,
+        mut write: impl FnMut(&mut W, &[u8]) -> fmt::Result,
+    ) -> fmt::Result {
+        let color = |b: &u8| self.get(ByteClass::of(*b));
+        let mut rest = bytes;
+        while let Some(first) = rest.first() {
+            let first = color(first);
+            let n = rest.iter().position(|b| color(b) != first);
+            let (run, tail) = rest.split_at(n.unwrap_or(rest.len()));
+            first.write_sgr(out)?;
+            write(out, run)?;
+            if first != Color::Default {
+                out.write_str("\x1b[0m")?;
+            }
+            rest = tail;
+        }
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for Palette {
+    type Err = PaletteError;
+
+    /// Parse comma-separated `class=color` pairs overriding the default palette
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Palette::default();
+        for pair in s.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (class, color) = pair.split_once('=').ok_or(PaletteError::InvalidSpec)?;
+            let class = ByteClass::ALL
+                .iter()
+                .find(|c| c.name() == class.trim())
+                .ok_or(PaletteError::InvalidClass)?;
+            r = r.with(*class, color.trim().parse()?);
+        }
+        Ok(r)
+    }
+}
+
+/**
+Error returned when parsing a [`Palette`] or [`Color`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaletteError {
+    /// Palette is not comma-separated `class=color` pairs
+    InvalidSpec,
+
+    /// Byte class name is unknown
+    InvalidClass,
+
+    /// Color is not a name, 0-255 or `#rrggbb`
+    InvalidColor,
+}
+
+impl core::fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            PaletteError::InvalidSpec => "Invalid palette! Must be `class=color,...`.",
+            PaletteError::InvalidClass => {
+                "Invalid byte class! Must be null, printable, whitespace, control, high or full."
+            }
+            PaletteError::InvalidColor => {
+                "Invalid color! Must be a color name, 0-255 or `#rrggbb`."
+            }
+        })
+    }
+}
+
+impl core::error::Error for PaletteError {}
@@ -0,0 +1,267 @@
+use {
+    crate::{DecodeError, Style},
+    alloc::{string::String, string::ToString, vec::Vec},
+    core::fmt,
+};
+
+/// Style that draws each packed BCD byte as the [`Style::Bcd`] cell of its 2 digits
+pub const PACKED_STYLE: Style = Style::Nlbt;
+
+/// Sign nibble of a positive or unsigned number
+const PLUS: u8 = 0xC;
+
+/// Sign nibble of a negative number
+const MINUS: u8 = 0xD;
+
+/**
+Integer type that can be packed as a decimal number; see [`pack_integer`]
+*/
+pub trait Integer: Copy + fmt::Display + core::str::FromStr {}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {})*
+    };
+}
+
+integer!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/**
+Pack a decimal number of any length with an optional `+` or `-` sign as packed BCD bytes
+
+Each byte holds 2 digits, most significant first, and the last byte holds the last digit and a
+sign nibble (`C`: positive, `D`: negative), like IBM packed decimal (COBOL `COMP-3`); a leading
+`0` digit is added if needed to fill the first byte. Since the sign nibble ends each number,
+packed numbers can be concatenated. Leading zeros are not kept and zero is always positive.
+
+In the [`PACKED_STYLE`] style used by [`encode_decimal`], a byte of 2 digits is drawn as the
+[`Style::Bcd`] cell of its value, so unlike [`encode_bcd`](crate::encode_bcd), any number can be
+encoded.
+
+```
+use bbd_lib::*;
+
+assert_eq!(pack_decimal("1234").unwrap(), [0x01, 0x23, 0x4c]);
+assert_eq!(pack_decimal("-0042").unwrap(), [0x04, 0x2d]);
+assert_eq!(pack_decimal("-0").unwrap(), [0x0c]);
+assert_eq!(pack_decimal("12a"), Err(DecimalError::InvalidNumber));
+```
+
+# Errors
+
+Returns an error if the number is not an optional sign followed by 1 or more ASCII digits
+*/
+pub fn pack_decimal(number: &str) -> Result<Vec<u8>, DecimalError> {
+    let (negative, digits) = match number.as_bytes() {
+        [b'-', digits @ ..] => (true, digits),
+        [b'+', digits @ ..] | digits => (false, digits),
+    };
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(DecimalError::InvalidNumber);
+    }
+    let zeros = digits.iter().take_while(|d| **d == b'0').count();
+    let digits = &digits[zeros.min(digits.len() - 1)..];
+    let sign = if negative && digits != b"0" {
+        MINUS
+    } else {
+        PLUS
+    };
+    let mut nibbles = Vec::with_capacity(digits.len() + 2);
+    if digits.len().is_multiple_of(2) {
+        nibbles.push(0);
+    }
+    nibbles.extend(digits.iter().map(|d| d - b'0'));
+    nibbles.push(sign);
+    Ok(nibbles
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/**
+Pack an integer as packed BCD bytes; see [`pack_decimal`]
+
+```
+use bbd_lib::*;
+
+assert_eq!(pack_integer(65535_u16), [0x65, 0x53, 0x5c]);
+assert_eq!(pack_integer(-1_i8), [0x1d]);
+assert_eq!(
+    unpack_integers::<u128>(&pack_integer(u128::MAX)).unwrap(),
+    [u128::MAX],
+);
+```
+*/
+#[must_use]
+#[allow(clippy::missing_panics_doc)]
+pub fn pack_integer<T: Integer>(value: T) -> Vec<u8> {
+    pack_decimal(&value.to_string()).unwrap()
+}
+
+/**
+Unpack concatenated packed BCD numbers; the inverse of [`pack_decimal`]
+
+Besides `C` and `D`, the other sign nibbles of IBM packed decimal are accepted: `B` is negative
+and `A`, `E` and `F` are positive.
+
+```
+use bbd_lib::*;
+
+assert_eq!(
+    unpack_decimals(&[0x01, 0x23, 0x4c, 0x04, 0x2d, 0x7f]).unwrap(),
+    ["1234", "-42", "7"],
+);
+assert_eq!(unpack_decimals(&[0x12, 0xa4]), Err(DecimalError::InvalidDigit { index: 1 }));
+assert_eq!(unpack_decimals(&[0x4c, 0x12]), Err(DecimalError::Unterminated));
+```
+
+# Errors
+
+Returns an error if a byte has a sign nibble in place of a digit or the last number has no sign
+nibble
+*/
+pub fn unpack_decimals(packed: &[u8]) -> Result<Vec<String>, DecimalError> {
+    let mut r = Vec::new();
+    let mut digits = String::new();
+    for (index, b) in packed.iter().enumerate() {
+        let (high, low) = (b >> 4, b & 0xF);
+        if high > 9 {
+            return Err(DecimalError::InvalidDigit { index });
+        }
+        digits.push(char::from(b'0' + high));
+        if low <= 9 {
+            digits.push(char::from(b'0' + low));
+            continue;
+        }
+        let trimmed = digits.trim_start_matches('0');
+        let mut number = String::with_capacity(trimmed.len() + 1);
+        if trimmed.is_empty() {
+            number.push('0');
+        } else {
+            if low == 0xB || low == MINUS {
+                number.push('-');
+            }
+            number.push_str(trimmed);
+        }
+        r.push(number);
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(DecimalError::Unterminated);
+    }
+    Ok(r)
+}
+
+/**
+Unpack concatenated packed BCD numbers as integers; see [`unpack_decimals`]
+
+```
+use bbd_lib::*;
+
+let mut packed = pack_integer(-32768_i16);
+packed.extend(pack_integer(32767_i16));
+assert_eq!(unpack_integers::<i16>(&packed).unwrap(), [-32768, 32767]);
+assert_eq!(unpack_integers::<u16>(&packed), Err(DecimalError::OutOfRange { number: 0 }));
+```
+
+# Errors
+
+Returns an error if unpacking fails or a number does not fit the integer type
+*/
+pub fn unpack_integers<T: Integer>(packed: &[u8]) -> Result<Vec<T>, DecimalError> {
+    unpack_decimals(packed)?
+        .iter()
+        .enumerate()
+        .map(|(number, n)| n.parse().or(Err(DecimalError::OutOfRange { number })))
+        .collect()
+}
+
+/**
+Encode a decimal number of any length as Braille cells of its packed BCD bytes; see
+[`pack_decimal`]
+
+```
+use bbd_lib::*;
+
+// The digit pairs are `bcd` cells
+assert_eq!(encode_decimal("1234").unwrap(), "⢀⢤⠚");
+assert_eq!(Style::Bcd.encode(&[1, 23], 0, 0), "⢀⢤");
+assert_eq!(encode_decimal("-42").unwrap(), "⠐⢜");
+```
+
+# Errors
+
+Returns an error if the number is not an optional sign followed by 1 or more ASCII digits
+*/
+pub fn encode_decimal(number: &str) -> Result<String, DecimalError> {
+    Ok(PACKED_STYLE.encode(&pack_decimal(number)?, 0, 0))
+}
+
+/**
+Decode Braille cells to concatenated decimal numbers; the inverse of [`encode_decimal`]
+
+Line wrapping (newlines and `\` continuation markers) is ignored.
+
+```
+use bbd_lib::*;
+
+assert_eq!(decode_decimals("⢀⢤⠚⠐\\\n⢜").unwrap(), ["1234", "-42"]);
+
+let error = decode_decimals("⢀⢤x").unwrap_err();
+assert!(matches!(error, DecimalError::Decode(DecodeError { character: 'x', .. })));
+```
+
+# Errors
+
+Returns an error if a character is not a Braille pattern or unpacking fails
+*/
+pub fn decode_decimals(content: &str) -> Result<Vec<String>, DecimalError> {
+    unpack_decimals(
+        &PACKED_STYLE
+            .try_decode(content)
+            .map_err(DecimalError::Decode)?,
+    )
+}
+
+/**
+Error returned when packing or unpacking decimal numbers
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecimalError {
+    /// Number is not an optional sign followed by 1 or more ASCII digits
+    InvalidNumber,
+
+    /// Byte (zero-based index) has a sign nibble in place of a digit
+    InvalidDigit { index: usize },
+
+    /// Last number has no sign nibble
+    Unterminated,
+
+    /// Number (zero-based index) does not fit the integer type
+    OutOfRange { number: usize },
+
+    /// Braille cells do not decode
+    Decode(DecodeError),
+}
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecimalError::InvalidNumber => {
+                f.write_str("Invalid decimal number! Must be an optional sign and digits.")
+            }
+            DecimalError::InvalidDigit { index } => {
+                write!(f, "Invalid packed BCD digit in byte {index}!")
+            }
+            DecimalError::Unterminated => f.write_str("Packed BCD number has no sign nibble!"),
+            DecimalError::OutOfRange { number } => {
+                write!(f, "Number {number} does not fit the integer type!")
+            }
+            DecimalError::Decode(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for DecimalError {}
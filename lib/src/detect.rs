@@ -0,0 +1,193 @@
+use {
+    crate::{Style, is_armored},
+    alloc::{vec, vec::Vec},
+};
+
+/// Maximum number of characters of the content used to score styles
+const SAMPLE: usize = 65536;
+
+/// Scores closer to the best score than this are considered ambiguous
+const MARGIN: f64 = 0.05;
+
+/**
+Result of [`detect_style`]
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+    /// Most likely style
+    pub style: Style,
+
+    /// Style was given by an explicit style header
+    pub header: bool,
+
+    /// Score (0-1) of each built-in style that is able to decode the content, sorted by
+    /// descending score; empty if the style was given by an explicit style header
+    pub scores: Vec<(Style, f64)>,
+}
+
+impl Detection {
+    /// Styles scoring within the ambiguity margin of the best score
+    #[must_use]
+    pub fn candidates(&self) -> Vec<Style> {
+        if self.header {
+            return vec![self.style];
+        }
+        let best = self.scores[0].1;
+        self.scores
+            .iter()
+            .take_while(|(_, score)| best - score < MARGIN)
+            .map(|(style, _)| *style)
+            .collect()
+    }
+
+    /// More than one style scores within the ambiguity margin of the best score
+    #[must_use]
+    pub fn is_ambiguous(&self) -> bool {
+        self.candidates().len() > 1
+    }
+}
+
+/**
+Detect the most likely style of binary representation
+
+If the content starts with an explicit style header (see [`style_header`]), its style is used.
+Otherwise every built-in style that is able to decode the content is scored on the decoded
+bytes:
+
+* `bcd`: Only valid if every cell is a valid BCD cell; the score is the probability that the
+  cells are not valid BCD cells by chance
+* Others: The ratio of printable ASCII and whitespace bytes plus a smaller weight for the ratio
+  of bytes common in binary data (NUL, 0xFF and small values)
+
+Returns [`None`] if no style is able to decode the content.
+
+```
+use bbd_lib::*;
+
+let detection = detect_style(&Style::Nrbt.encode(b"The quick brown fox", 0, 0)).unwrap();
+assert_eq!(detection.style, Style::Nrbt);
+assert!(!detection.is_ambiguous());
+
+let decimals = (0..=99).collect::<Vec<u8>>();
+assert_eq!(detect_style(&Style::Bcd.encode(&decimals, 64, 0)).unwrap().style, Style::Bcd);
+
+// Explicit style header
+let detection = detect_style("Style: nlbt\n\n⢄⠮⢦⢦⢾⢐").unwrap();
+assert_eq!(detection.style, Style::Nlbt);
+assert!(detection.header);
+
+// Every permutation of a blank or full cell is the same
+assert!(detect_style("⠀⣿⠀⣿").unwrap().is_ambiguous());
+
+assert_eq!(detect_style("Hello"), None);
+```
+*/
+#[must_use]
+pub fn detect_style(content: &str) -> Option<Detection> {
+    if let Some((style, _)) = style_header(content) {
+        return Some(Detection {
+            style,
+            header: true,
+            scores: vec![],
+        });
+    }
+
+    let sample = match content.char_indices().nth(SAMPLE) {
+        Some((i, _)) => &content[..i],
+        None => content,
+    };
+    let mut scores = Style::ALL
+        .iter()
+        .filter_map(|style| {
+            let decoded = style.try_decode(sample).ok()?;
+            if decoded.is_empty() {
+                return None;
+            }
+            Some((*style, score(*style, &decoded)))
+        })
+        .collect::<Vec<_>>();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    Some(Detection {
+        style: scores.first()?.0,
+        header: false,
+        scores,
+    })
+}
+
+/**
+Score the plausibility of bytes decoded using the given style
+*/
+#[allow(clippy::cast_precision_loss)]
+fn score(style: Style, decoded: &[u8]) -> f64 {
+    let n = decoded.len() as f64;
+    if style == Style::Bcd {
+        // `f64::powi` needs `std`; the probability underflows long before 1024 cells
+        let chance = (0..decoded.len().min(1024)).fold(1.0, |p, _| p * 100.0 / 256.0);
+        return 1.0 - chance;
+    }
+    let printable = decoded
+        .iter()
+        .filter(|b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+        .count() as f64;
+    let common = decoded
+        .iter()
+        .filter(|b| **b == 0 || **b == 0xFF || **b < 0x10)
+        .count() as f64;
+    0.8 * printable / n + 0.2 * (printable + common).min(n) / n
+}
+
+/**
+Read an explicit style header from the start of the content
+
+A header is one or more lines of the form `Key: Value` at the start of the content, optionally
+followed by a blank line, before the binary representation. The style is given by a `Style` key
+(case insensitive) with a value that parses as a [`Style`]. The header of an [`Armor`](crate::Armor)
+is recognised after its `BEGIN` marker line; use [`Armor::decode`](crate::Armor::decode) to also
+remove the `END` marker line and verify the content.
+
+Returns the style and the rest of the content after the header, or [`None`] if there is no header
+with a valid style.
+
+```
+use bbd_lib::*;
+
+let (style, body) = style_header("Style: custom:4,5,6,8,1,2,3,7\nLength: 6\n\n⢄⠮⢦⢦⢾⢐").unwrap();
+assert_eq!(style.decode(body), b"Hello\n");
+
+assert_eq!(style_header("Style: nlbb\n⢄⠮⢦⢦⢾⢐"), Some((Style::Nlbb, "⢄⠮⢦⢦⢾⢐")));
+assert_eq!(style_header("⢄⠮⢦⢦⢾⢐"), None);
+assert_eq!(style_header("Style: nope\n\n⢄⠮⢦⢦⢾⢐"), None);
+
+let armored = Armor::new(b"Hello\n".to_vec(), Style::Nrbb, 64).encode();
+assert_eq!(style_header(&armored).unwrap().0, Style::Nrbb);
+assert_eq!(detect_style(&armored).unwrap().style, Style::Nrbb);
+```
+*/
+#[must_use]
+pub fn style_header(content: &str) -> Option<(Style, &str)> {
+    let mut style = None;
+    let mut rest = content;
+    if is_armored(content) {
+        rest = content.trim_start().split_once('\n')?.1;
+    }
+    while let Some((line, tail)) = rest.split_once('\n').or(Some((rest, "")))
+        && !line.is_empty()
+    {
+        let Some((key, value)) = line.trim_end_matches('\r').split_once(": ") else {
+            break;
+        };
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            break;
+        }
+        if key.eq_ignore_ascii_case("style") {
+            style = Some(value.trim().parse().ok()?);
+        }
+        rest = tail;
+    }
+    let rest = rest
+        .strip_prefix("\r\n")
+        .or(rest.strip_prefix('\n'))
+        .unwrap_or(rest);
+    Some((style?, rest))
+}
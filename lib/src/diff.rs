@@ -0,0 +1,421 @@
+use {
+    crate::{Style, encode_direct, try_decode_direct},
+    alloc::{string::String, vec, vec::Vec},
+    core::fmt::{self, Write},
+};
+
+/// Maximum number of insertions and deletions that [`Alignment::Myers`] searches for
+pub const MYERS_MAX_EDITS: usize = 2048;
+
+/// Cell shown for a gap in an aligned byte stream
+pub const GAP: char = '·';
+
+/**
+How [`align`] lines up two byte streams
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Compare byte by byte at the same offsets; the shorter stream is padded with gaps
+    #[default]
+    Offset,
+
+    /// Find the fewest insertions and deletions (Myers), so that an inserted or deleted byte does
+    /// not shift the rest of the streams out of line
+    Myers,
+}
+
+/**
+Pair of aligned bytes; [`None`] is a gap where the byte was inserted into the other stream or
+deleted from it
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Aligned {
+    /// Byte of the left (old) stream
+    pub left: Option<u8>,
+
+    /// Byte of the right (new) stream
+    pub right: Option<u8>,
+}
+
+impl Aligned {
+    /// Both bytes are present and equal
+    #[must_use]
+    pub fn is_equal(self) -> bool {
+        self.left.is_some() && self.left == self.right
+    }
+}
+
+/**
+Line up two byte streams
+
+With [`Alignment::Myers`], the common prefix and suffix are matched first, then the fewest
+insertions and deletions are searched for in between. Adjacent deletions and insertions are paired
+up as changed bytes. If more than [`MYERS_MAX_EDITS`] insertions and deletions are needed, the
+bytes in between are compared by offset instead.
+
+```
+use bbd_lib::*;
+
+let (old, new) = (b"Hello, world!", b"Hello, big world?");
+
+let aligned = align(old, new, Alignment::Offset);
+assert_eq!(aligned.len(), 17);
+assert_eq!(aligned.iter().filter(|a| !a.is_equal()).count(), 10);
+
+let aligned = align(old, new, Alignment::Myers);
+assert_eq!(aligned.len(), 17);
+assert_eq!(aligned.iter().filter(|a| !a.is_equal()).count(), 5);
+assert_eq!(aligned[7], Aligned { left: None, right: Some(b'b') });
+assert_eq!(aligned[16], Aligned { left: Some(b'!'), right: Some(b'?') });
+```
+*/
+#[must_use]
+pub fn align(left: &[u8], right: &[u8], alignment: Alignment) -> Vec<Aligned> {
+    let same = |l: &u8, r: &u8| Aligned {
+        left: Some(*l),
+        right: Some(*r),
+    };
+    match alignment {
+        Alignment::Offset => {
+            let mut r = left
+                .iter()
+                .zip(right)
+                .map(|(l, r)| same(l, r))
+                .collect::<Vec<_>>();
+            r.extend(left[r.len()..].iter().map(|l| Aligned {
+                left: Some(*l),
+                right: None,
+            }));
+            r.extend(right[r.len().min(right.len())..].iter().map(|r| Aligned {
+                left: None,
+                right: Some(*r),
+            }));
+            r
+        }
+        Alignment::Myers => {
+            let prefix = left.iter().zip(right).take_while(|(l, r)| l == r).count();
+            let (left_rest, right_rest) = (&left[prefix..], &right[prefix..]);
+            let suffix = left_rest
+                .iter()
+                .rev()
+                .zip(right_rest.iter().rev())
+                .take_while(|(l, r)| l == r)
+                .count();
+            let (left_middle, right_middle) = (
+                &left_rest[..left_rest.len() - suffix],
+                &right_rest[..right_rest.len() - suffix],
+            );
+            let mut r = left[..prefix]
+                .iter()
+                .map(|b| same(b, b))
+                .collect::<Vec<_>>();
+            match myers(left_middle, right_middle) {
+                Some(middle) => r.extend(pair(&middle)),
+                None => r.extend(align(left_middle, right_middle, Alignment::Offset)),
+            }
+            r.extend(
+                left_rest[left_rest.len() - suffix..]
+                    .iter()
+                    .map(|b| same(b, b)),
+            );
+            r
+        }
+    }
+}
+
+/**
+Fewest insertions and deletions to turn `left` into `right` (Myers' O(ND) algorithm), or [`None`]
+if more than [`MYERS_MAX_EDITS`] are needed
+*/
+fn myers(left: &[u8], right: &[u8]) -> Option<Vec<Aligned>> {
+    let max = (left.len() + right.len()).min(MYERS_MAX_EDITS);
+    // Furthest `x` on diagonal `k = x - y`, stored at index `k + max + 1`
+    let mut furthest = vec![0; 2 * max + 3];
+    let center = max + 1;
+    // State of `furthest` before each step `d`, for diagonals `-d..=d`
+    let mut trace = Vec::<Vec<usize>>::new();
+    for d in 0..=max {
+        trace.push(furthest[center - d..=center + d].to_vec());
+        for k in (center - d..=center + d).step_by(2) {
+            let mut x = if k == center - d || (k != center + d && furthest[k - 1] < furthest[k + 1])
+            {
+                furthest[k + 1]
+            } else {
+                furthest[k - 1] + 1
+            };
+            let mut y = (x + center).checked_sub(k)?;
+            while x < left.len() && y < right.len() && left[x] == right[y] {
+                x += 1;
+                y += 1;
+            }
+            furthest[k] = x;
+            if x >= left.len() && y >= right.len() {
+                return Some(backtrack(left, right, &trace, d, k + d - center));
+            }
+        }
+    }
+    None
+}
+
+/// Walk the trace of [`myers`] back from the end reached on step `d` at diagonal index `k`
+fn backtrack(
+    left: &[u8],
+    right: &[u8],
+    trace: &[Vec<usize>],
+    d: usize,
+    mut k: usize,
+) -> Vec<Aligned> {
+    let mut aligned = Vec::with_capacity(left.len().max(right.len()) + d);
+    let (mut x, mut y) = (left.len(), right.len());
+    for d in (1..=d).rev() {
+        // `furthest` before step `d` covers diagonals `-d..=d` at indexes `0..=2d`
+        let furthest = &trace[d];
+        let down = k == 0 || (k != 2 * d && furthest[k - 1] < furthest[k + 1]);
+        let prev_k = if down { k + 1 } else { k - 1 };
+        let prev_x = furthest[prev_k];
+        // Diagonal `prev_k - d`
+        let prev_y = prev_x + d - prev_k;
+        let (start_x, start_y) = if down {
+            (prev_x, prev_y + 1)
+        } else {
+            (prev_x + 1, prev_y)
+        };
+        while x > start_x && y > start_y {
+            x -= 1;
+            y -= 1;
+            aligned.push(Aligned {
+                left: Some(left[x]),
+                right: Some(right[y]),
+            });
+        }
+        if down {
+            aligned.push(Aligned {
+                left: None,
+                right: Some(right[prev_y]),
+            });
+        } else {
+            aligned.push(Aligned {
+                left: Some(left[prev_x]),
+                right: None,
+            });
+        }
+        (x, y) = (prev_x, prev_y);
+        // Index of the previous diagonal in the trace of the previous step
+        k = prev_k - 1;
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        aligned.push(Aligned {
+            left: Some(left[x]),
+            right: Some(right[y]),
+        });
+    }
+    aligned.reverse();
+    aligned
+}
+
+/// Pair up adjacent deletions and insertions as changed bytes
+fn pair(aligned: &[Aligned]) -> Vec<Aligned> {
+    let mut r = Vec::with_capacity(aligned.len());
+    let mut rest = aligned;
+    while !rest.is_empty() {
+        let n = rest
+            .iter()
+            .position(|a| a.left.is_some() && a.right.is_some())
+            .unwrap_or(rest.len());
+        if n == 0 {
+            r.push(rest[0]);
+            rest = &rest[1..];
+            continue;
+        }
+        let (gaps, tail) = rest.split_at(n);
+        let deleted = gaps.iter().filter_map(|a| a.left).collect::<Vec<u8>>();
+        let inserted = gaps.iter().filter_map(|a| a.right).collect::<Vec<u8>>();
+        r.extend(align(&deleted, &inserted, Alignment::Offset));
+        rest = tail;
+    }
+    r
+}
+
+/**
+Diff layout of aligned byte streams as Braille cells; see [`align`]
+
+Each line shows `columns` aligned pairs. Side by side, a line is the offset and cells of the left
+stream, a `|` separator if the line differs, and the offset and cells of the right stream.
+Interleaved, a differing line is shown as a `-` line for the left stream and a `+` line for the
+right stream like a unified diff. Gaps are shown as [`GAP`]. The optional XOR row (`x`) draws only
+the dots that differ between the cells. With highlighting, the differing cells of the left and
+right streams are colored red and green with ANSI escape sequences.
+
+```
+use bbd_lib::*;
+
+let aligned = align(b"Hello!", b"Hullo?!", Alignment::Myers);
+let diff = Diff { columns: 8, ..Diff::new(Style::Nlbb) };
+assert_eq!(
+    diff.format(&aligned),
+    "-00000000  ⢄⠮⢦⢦⢾·⠊\n+00000000  ⢄⠯⢦⢦⢾⢻⠊\n",
+);
+
+let diff = Diff { side_by_side: true, xor: true, ..diff };
+assert_eq!(
+    diff.format(&aligned),
+    "00000000  ⢄⠮⢦⢦⢾·⠊   |  00000000  ⢄⠯⢦⢦⢾⢻⠊   ⠀⠁⠀⠀⠀⢻⠀\n",
+);
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Diff {
+    /// Style of the cells
+    pub style: Style,
+
+    /// Aligned pairs per line
+    pub columns: usize,
+
+    /// Show the streams side by side instead of interleaved
+    pub side_by_side: bool,
+
+    /// Show the XOR row
+    pub xor: bool,
+
+    /// Show the lines without differences too
+    pub all: bool,
+
+    /// Color the differing cells
+    pub highlight: bool,
+}
+
+impl Diff {
+    /// Create an interleaved diff layout with 16 pairs per line using the given style
+    #[must_use]
+    pub fn new(style: Style) -> Diff {
+        Diff {
+            style,
+            columns: 16,
+            side_by_side: false,
+            xor: false,
+            all: false,
+            highlight: false,
+        }
+    }
+
+    /**
+    Format aligned pairs to lines
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and a byte is greater than 99
+    */
+    #[must_use]
+    pub fn format(&self, aligned: &[Aligned]) -> String {
+        let mut r = String::new();
+        self.write(aligned, &mut r).unwrap();
+        r
+    }
+
+    /**
+    Write aligned pairs as lines
+
+    # Errors
+
+    Returns an error if writing fails
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and a byte is greater than 99
+    */
+    pub fn write(&self, aligned: &[Aligned], out: &mut impl Write) -> fmt::Result {
+        let columns = if self.columns == 0 {
+            aligned.len().max(1)
+        } else {
+            self.columns
+        };
+        let (mut left, mut right) = (0, 0);
+        for line in aligned.chunks(columns) {
+            let differs = line.iter().any(|a| !a.is_equal());
+            if differs || self.all {
+                self.write_line(line, columns, (left, right), differs, out)?;
+            }
+            left += line.iter().filter(|a| a.left.is_some()).count();
+            right += line.iter().filter(|a| a.right.is_some()).count();
+        }
+        Ok(())
+    }
+
+    /// Write a line at the given left and right offsets
+    fn write_line(
+        &self,
+        line: &[Aligned],
+        columns: usize,
+        (left, right): (usize, usize),
+        differs: bool,
+        out: &mut impl Write,
+    ) -> fmt::Result {
+        let padding = columns - line.len();
+        if self.side_by_side {
+            write!(out, "{left:08x}  ")?;
+            self.write_cells(line, |a| a.left, "\x1b[31m", out)?;
+            pad(out, padding)?;
+            out.write_str(if differs { "  |  " } else { "     " })?;
+            write!(out, "{right:08x}  ")?;
+            self.write_cells(line, |a| a.right, "\x1b[32m", out)?;
+            if self.xor {
+                pad(out, padding + 2)?;
+                self.write_xor(line, out)?;
+            }
+        } else if differs {
+            write!(out, "-{left:08x}  ")?;
+            self.write_cells(line, |a| a.left, "\x1b[31m", out)?;
+            write!(out, "\n+{right:08x}  ")?;
+            self.write_cells(line, |a| a.right, "\x1b[32m", out)?;
+            if self.xor {
+                out.write_str("\nx          ")?;
+                self.write_xor(line, out)?;
+            }
+        } else {
+            write!(out, " {left:08x}  ")?;
+            self.write_cells(line, |a| a.left, "", out)?;
+        }
+        out.write_char('\n')
+    }
+
+    /// Write the cells of one stream; differing cells are wrapped in the given color if
+    /// highlighting
+    fn write_cells(
+        &self,
+        line: &[Aligned],
+        side: impl Fn(&Aligned) -> Option<u8>,
+        color: &str,
+        out: &mut impl Write,
+    ) -> fmt::Result {
+        for a in line {
+            let cell = side(a).map_or(GAP, |b| self.style.encode_byte(b));
+            if self.highlight && !a.is_equal() && !color.is_empty() {
+                write!(out, "{color}{cell}\x1b[0m")?;
+            } else {
+                out.write_char(cell)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the XOR row
+    fn write_xor(&self, line: &[Aligned], out: &mut impl Write) -> fmt::Result {
+        let dots = |b: Option<u8>| {
+            b.map_or(0, |b| {
+                try_decode_direct(self.style.encode_byte(b)).unwrap_or_default()
+            })
+        };
+        for a in line {
+            out.write_char(encode_direct(dots(a.left) ^ dots(a.right)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the given number of spaces
+fn pad(out: &mut impl Write, n: usize) -> fmt::Result {
+    (0..n).try_for_each(|_| out.write_char(' '))
+}
@@ -0,0 +1,196 @@
+use {
+    crate::{Palette, Style, Tables},
+    core::fmt::{self, Write},
+};
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/**
+Stateful encoder that keeps the wrap position across calls
+
+Unlike [`encode`](crate::encode), the caller does not have to track the length of the content
+encoded so far: each [`push`](Encoder::push) continues the current line. Each full line of `columns`
+bytes is written as the line prefix, the cells (colored with the optional [`Palette`]), the
+continuation marker, the line suffix and a newline; [`finish`](Encoder::finish) ends the trailing
+line with the line suffix. With the defaults (continuation marker `\\`, no line prefix or suffix),
+the output is the same as [`encode`](crate::encode) for the concatenated content.
+
+```
+use bbd_lib::*;
+
+let content = (0..=255).collect::<Vec<u8>>();
+
+let mut encoder = Encoder::new(Style::Nlbb).with_columns(10);
+let mut encoded = String::new();
+for chunk in content.chunks(7) {
+    encoded.push_str(&encoder.push(chunk));
+}
+encoded.push_str(&encoder.finish());
+assert_eq!(encoded, encode(&content, encode_nlbb, 10, 0));
+
+let mut encoder = Encoder::new(Style::Nlbb)
+    .with_columns(4)
+    .with_marker("")
+    .with_prefix("> ")
+    .with_suffix(" <");
+let mut encoded = encoder.push(b"Hel");
+encoded.push_str(&encoder.push(b"lo\n"));
+encoded.push_str(&encoder.finish());
+assert_eq!(encoded, "> ⢄⠮⢦⢦ <\n> ⢾⢐ <");
+```
+*/
+#[derive(Clone)]
+pub struct Encoder<'a> {
+    tables: Tables,
+    columns: usize,
+    marker: &'a str,
+    prefix: &'a str,
+    suffix: &'a str,
+    palette: Option<Palette>,
+    column: usize,
+}
+
+impl<'a> Encoder<'a> {
+    /// Create an encoder for the given style without wrapping
+    #[must_use]
+    pub fn new(style: Style) -> Encoder<'a> {
+        Encoder {
+            tables: style.tables(),
+            columns: 0,
+            marker: "\\",
+            prefix: "",
+            suffix: "",
+            palette: None,
+            column: 0,
+        }
+    }
+
+    /// Set the columns ("bytes") per line; 0: no wrapping
+    #[must_use]
+    pub fn with_columns(mut self, columns: usize) -> Encoder<'a> {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the continuation marker at the end of each full line (default: `\\`)
+    #[must_use]
+    pub fn with_marker(mut self, marker: &'a str) -> Encoder<'a> {
+        self.marker = marker;
+        self
+    }
+
+    /// Set the text at the start of each line
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: &'a str) -> Encoder<'a> {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Set the text at the end of each line, after the continuation marker
+    #[must_use]
+    pub fn with_suffix(mut self, suffix: &'a str) -> Encoder<'a> {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Color the cells by byte class
+    #[must_use]
+    pub fn with_palette(mut self, palette: Palette) -> Encoder<'a> {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Number of bytes in the current line
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /**
+    Encode bytes to binary representation continuing the current line
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+    */
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn push(&mut self, content: &[u8]) -> String {
+        let mut r = String::with_capacity(4 * content.len());
+        self.push_into(content, &mut r).unwrap();
+        r
+    }
+
+    /**
+    Encode bytes to binary representation continuing the current line, appending to the given
+    [`String`] or other [`core::fmt::Write`] without allocating
+
+    # Errors
+
+    Returns an error if writing to the output fails
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+    */
+    pub fn push_into(&mut self, content: &[u8], out: &mut impl Write) -> fmt::Result {
+        let mut rest = content;
+        while !rest.is_empty() {
+            if self.column == 0 {
+                out.write_str(self.prefix)?;
+            }
+            let n = if self.columns > 0 {
+                self.columns - self.column
+            } else {
+                rest.len()
+            };
+            let (line, tail) = rest.split_at(n.min(rest.len()));
+            let tables = &self.tables;
+            if let Some(palette) = self.palette {
+                palette.write_runs(line, out, |out, run| tables.encode_to(run, 0, &mut 0, out))?;
+            } else {
+                tables.encode_to(line, 0, &mut 0, out)?;
+            }
+            self.column += line.len();
+            if self.columns > 0 && self.column >= self.columns {
+                out.write_str(self.marker)?;
+                out.write_str(self.suffix)?;
+                out.write_char('\n')?;
+                self.column = 0;
+            }
+            rest = tail;
+        }
+        Ok(())
+    }
+
+    /**
+    End the trailing line, if any, with the line suffix and start over at the first column
+
+    There is no newline after the trailing line, like [`encode`](crate::encode).
+    */
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn finish(&mut self) -> String {
+        let mut r = String::new();
+        self.finish_into(&mut r).unwrap();
+        r
+    }
+
+    /**
+    End the trailing line, if any, with the line suffix and start over at the first column,
+    appending to the given [`String`] or other [`core::fmt::Write`]
+
+    # Errors
+
+    Returns an error if writing to the output fails
+    */
+    pub fn finish_into(&mut self, out: &mut impl Write) -> fmt::Result {
+        if self.column > 0 {
+            out.write_str(self.suffix)?;
+            self.column = 0;
+        }
+        Ok(())
+    }
+}
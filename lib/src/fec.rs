@@ -0,0 +1,353 @@
+use {
+    crate::{DecodeErrorKind, Style, encode_direct},
+    alloc::{string::String, vec, vec::Vec},
+};
+
+/// Number of cells in a full Reed-Solomon block
+pub const FEC_BLOCK: usize = 255;
+
+/// Powers of the generator (2) of GF(256) with the primitive polynomial 0x11D, twice over so that
+/// the sum of two logarithms needs no modulo
+const EXP: [u8; 512] = gf_exp();
+
+/// Logarithms of GF(256); `LOG[0]` is unused
+const LOG: [u8; 256] = gf_log();
+
+/**
+Encode bytes to binary representation with Reed-Solomon forward error correction
+
+The content is split into blocks of up to `255 - 2 * errors` bytes (the last block is shortened).
+Each byte is encoded to a cell using the given style, then `2 * errors` parity cells are appended
+to each block. The code works on the dot patterns of the cells, so the parity cells are drawn in
+the [`Style::Direct`] style and any style can be protected, including [`Style::Bcd`].
+[`decode_fec`] repairs up to `errors` wrong cells per block. The cells are wrapped like
+[`encode`](crate::encode).
+
+```
+use bbd_lib::*;
+
+let encoded = encode_fec(b"Hello\n", Style::Nlbb, 2, 0);
+assert_eq!(encoded.chars().count(), 6 + 4);
+assert!(encoded.starts_with("⢄⠮⢦⢦⢾⢐"));
+
+// Repair 2 wrong cells
+let damaged = encoded.replacen('⠮', "⣿", 1).replacen('⢐', "x", 1);
+let corrected = decode_fec(&damaged, Style::Nlbb, 2).unwrap();
+assert_eq!(corrected.content, b"Hello\n");
+assert_eq!(corrected.corrections, 2);
+
+// 3 wrong cells are too many
+let damaged = damaged.replacen('⢾', "⠀", 1);
+assert_eq!(decode_fec(&damaged, Style::Nlbb, 2), Err(FecError::Uncorrectable { block: 0 }));
+```
+
+# Panics
+
+Panics if `errors` is not 1-127 or if the style is [`Style::Bcd`] and the content has a byte
+greater than 99
+*/
+#[must_use]
+pub fn encode_fec(content: &[u8], style: Style, errors: usize, columns: usize) -> String {
+    assert!(
+        (1..=127).contains(&errors),
+        "Correctable errors per block must be 1-127!",
+    );
+    let parity = 2 * errors;
+    let generator = generator(parity);
+    let mut cells =
+        Vec::with_capacity(content.len() + content.len().div_ceil(255 - parity) * parity);
+    for block in content.chunks(FEC_BLOCK - parity) {
+        let start = cells.len();
+        cells.extend(block.iter().map(|b| dots(style.encode_byte(*b))));
+        let remainder = remainder(&cells[start..], &generator);
+        cells.extend(remainder);
+    }
+    Style::Direct.encode(&cells, columns, 0)
+}
+
+/**
+Decode binary representation with Reed-Solomon forward error correction; see [`encode_fec`]
+
+Continuation markers (`\\`) and newlines are ignored. Any other character that is not a Braille
+pattern is treated as a wrong cell. The given style and `errors` must match the encoding.
+
+# Errors
+
+Returns an error if `errors` is not 1-127, a block has more wrong cells than can be repaired, the
+last block is shorter than its parity cells, or a repaired cell is not valid in the given style
+*/
+pub fn decode_fec(content: &str, style: Style, errors: usize) -> Result<Corrected, FecError> {
+    if !(1..=127).contains(&errors) {
+        return Err(FecError::InvalidErrors);
+    }
+    let parity = 2 * errors;
+    // Characters that are not Braille patterns are wrong cells
+    let (mut cells, braille): (Vec<u8>, Vec<bool>) = content
+        .chars()
+        .filter(|c| !['\\', '\n', '\r'].contains(c))
+        .map(|c| match c {
+            '\u{2800}'..='\u{28FF}' => (dots(c), true),
+            _ => (0, false),
+        })
+        .unzip();
+
+    let mut r = Corrected::default();
+    for (block, (cells, braille)) in cells
+        .chunks_mut(FEC_BLOCK)
+        .zip(braille.chunks(FEC_BLOCK))
+        .enumerate()
+    {
+        if cells.len() <= parity {
+            return Err(FecError::Truncated);
+        }
+        let received = cells.to_vec();
+        correct(cells, parity).ok_or(FecError::Uncorrectable { block })?;
+        r.corrections += cells
+            .iter()
+            .zip(&received)
+            .zip(braille)
+            .filter(|((cell, received), braille)| cell != received || !**braille)
+            .count();
+        r.blocks += 1;
+        for dots in &cells[..cells.len() - parity] {
+            let b = style
+                .try_decode_char(encode_direct(*dots))
+                .map_err(|kind| FecError::Decode {
+                    offset: r.content.len(),
+                    kind,
+                })?;
+            r.content.push(b);
+        }
+    }
+    Ok(r)
+}
+
+/**
+Result of [`decode_fec`]
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Corrected {
+    /// Decoded bytes
+    pub content: Vec<u8>,
+
+    /// Number of wrong cells repaired
+    pub corrections: usize,
+
+    /// Number of blocks
+    pub blocks: usize,
+}
+
+/**
+Error returned by [`decode_fec`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FecError {
+    /// Correctable errors per block is not 1-127
+    InvalidErrors,
+
+    /// Block (zero-based) has more wrong cells than can be repaired
+    Uncorrectable { block: usize },
+
+    /// Last block is not longer than its parity cells
+    Truncated,
+
+    /// Repaired cell at the given offset of the content is not valid in the style
+    Decode {
+        offset: usize,
+        kind: DecodeErrorKind,
+    },
+}
+
+impl core::fmt::Display for FecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            FecError::InvalidErrors => f.write_str("Correctable errors per block must be 1-127!"),
+            FecError::Uncorrectable { block } => {
+                write!(f, "Block {block} has too many wrong cells to repair!")
+            }
+            FecError::Truncated => f.write_str("Last block is truncated!"),
+            FecError::Decode { offset, kind } => {
+                write!(f, "Invalid cell at offset {offset}: {kind}!")
+            }
+        }
+    }
+}
+
+impl core::error::Error for FecError {}
+
+/// Dot pattern of a Braille cell
+fn dots(c: char) -> u8 {
+    #[allow(clippy::cast_possible_truncation)]
+    let r = (u32::from(c) - 0x2800) as u8;
+    r
+}
+
+/// Multiply in GF(256)
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        EXP[usize::from(LOG[usize::from(a)]) + usize::from(LOG[usize::from(b)])]
+    }
+}
+
+/// Divide in GF(256); `b` must not be 0
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        0
+    } else {
+        EXP[usize::from(LOG[usize::from(a)]) + 255 - usize::from(LOG[usize::from(b)])]
+    }
+}
+
+/// Evaluate a polynomial with coefficients from the highest degree
+fn eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().fold(0, |r, c| mul(r, x) ^ c)
+}
+
+/// Generator polynomial with roots 2^0 to 2^(parity-1); coefficients from the highest degree
+fn generator(parity: usize) -> Vec<u8> {
+    let mut r = vec![1];
+    for root in &EXP[..parity] {
+        // Multiply by (x + root)
+        r.push(0);
+        for j in (1..r.len()).rev() {
+            r[j] ^= mul(r[j - 1], *root);
+        }
+    }
+    r
+}
+
+/// Parity of a block: the remainder of the block times x^parity divided by the generator
+fn remainder(block: &[u8], generator: &[u8]) -> Vec<u8> {
+    let mut r = vec![0; generator.len() - 1];
+    for b in block {
+        let feedback = b ^ r[0];
+        r.rotate_left(1);
+        *r.last_mut().unwrap() = 0;
+        for (r, g) in r.iter_mut().zip(&generator[1..]) {
+            *r ^= mul(*g, feedback);
+        }
+    }
+    r
+}
+
+/**
+Repair a block (data and parity cells) in place; returns the number of repaired cells or [`None`]
+if there are too many wrong cells
+
+The cell at index `i` is the coefficient of degree `n - 1 - i`.
+*/
+fn correct(block: &mut [u8], parity: usize) -> Option<usize> {
+    let n = block.len();
+    let syndromes = (0..parity)
+        .map(|i| eval(block, EXP[i]))
+        .collect::<Vec<u8>>();
+    if syndromes.iter().all(|s| *s == 0) {
+        return Some(0);
+    }
+
+    // Berlekamp-Massey: error locator polynomial with coefficients from the lowest degree
+    let (mut locator, mut previous) = (vec![1], vec![1]);
+    let (mut errors, mut shift, mut discrepancy) = (0, 1, 1);
+    for k in 0..parity {
+        let d = (1..=errors.min(locator.len() - 1))
+            .fold(syndromes[k], |d, i| d ^ mul(locator[i], syndromes[k - i]));
+        if d == 0 {
+            shift += 1;
+            continue;
+        }
+        let scale = div(d, discrepancy);
+        let mut next = locator.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (i, p) in previous.iter().enumerate() {
+            next[i + shift] ^= mul(scale, *p);
+        }
+        if 2 * errors <= k {
+            previous = locator;
+            errors = k + 1 - errors;
+            discrepancy = d;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        locator = next;
+    }
+    if 2 * errors > parity {
+        return None;
+    }
+
+    // Chien search: the cell of degree `j` is wrong if the locator has a root at 2^-j
+    let positions = (0..n)
+        .filter(|j| {
+            let x = EXP[255 - j];
+            locator.iter().rev().fold(0, |r, c| mul(r, x) ^ c) == 0
+        })
+        .collect::<Vec<usize>>();
+    if positions.len() != errors {
+        return None;
+    }
+
+    // Forney: error evaluator is the syndromes times the locator modulo x^parity
+    let mut evaluator = vec![0; parity];
+    for (i, s) in syndromes.iter().enumerate() {
+        for (j, l) in locator.iter().enumerate().take(parity - i) {
+            evaluator[i + j] ^= mul(*s, *l);
+        }
+    }
+    for j in &positions {
+        let (x, x_inv) = (EXP[*j], EXP[255 - j]);
+        let numerator = evaluator.iter().rev().fold(0, |r, c| mul(r, x_inv) ^ c);
+        // Formal derivative of the locator: only the odd degree terms remain, as even powers
+        let denominator = locator
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .rev()
+            .fold(0, |r, c| mul(r, mul(x_inv, x_inv)) ^ c);
+        if denominator == 0 {
+            return None;
+        }
+        block[n - 1 - j] ^= mul(x, div(numerator, denominator));
+    }
+
+    if (0..parity).any(|i| eval(block, EXP[i]) != 0) {
+        return None;
+    }
+    Some(errors)
+}
+
+/// Build the powers table
+const fn gf_exp() -> [u8; 512] {
+    let mut r = [0; 512];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            r[i] = x as u8;
+            r[i + 255] = x as u8;
+        }
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11D;
+        }
+        i += 1;
+    }
+    r
+}
+
+/// Build the logarithms table
+const fn gf_log() -> [u8; 256] {
+    let exp = gf_exp();
+    let mut r = [0; 256];
+    let mut i = 0;
+    while i < 255 {
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            r[exp[i] as usize] = i as u8;
+        }
+        i += 1;
+    }
+    r
+}
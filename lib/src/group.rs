@@ -0,0 +1,201 @@
+use core::fmt::{self, Write};
+
+#[cfg(feature = "alloc")]
+use {
+    crate::{DecodeError, Style},
+    alloc::{string::String, vec::Vec},
+};
+
+/**
+Grouping of bytes like `xxd -g` and `xxd -e`
+
+Groups of `size` bytes are separated by a space and, for little-endian display, the bytes within
+each group are reversed so that 16, 32 and 64-bit words read most significant byte first. Groups
+restart at the start of each line; a short last group is reversed as is.
+
+```
+use bbd_lib::*;
+
+let content = 0x12345678_u32.to_le_bytes();
+assert_eq!(encode_grouped(&content, Style::Nlbb, 0, Grouping::new(2)), "⢇⠵ ⠣⠑");
+assert_eq!(
+    encode_grouped(&content, Style::Nlbb, 0, Grouping::little_endian(4)),
+    Style::Nlbb.encode(&0x12345678_u32.to_be_bytes(), 0, 0),
+);
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Grouping {
+    /// Bytes per group; 0: no grouping
+    pub size: usize,
+
+    /// Reverse the bytes within each group
+    pub little_endian: bool,
+}
+
+impl Grouping {
+    /// Groups of the given number of bytes in order
+    #[must_use]
+    pub fn new(size: usize) -> Grouping {
+        Grouping {
+            size,
+            little_endian: false,
+        }
+    }
+
+    /// Groups of the given number of bytes with the bytes within each group reversed
+    #[must_use]
+    pub fn little_endian(size: usize) -> Grouping {
+        Grouping {
+            size,
+            little_endian: true,
+        }
+    }
+
+    /// Width in characters of the given number of bytes with `per_byte` characters per byte
+    pub(crate) fn width(self, bytes: usize, per_byte: usize) -> usize {
+        let separators = match self.size {
+            0 => 0,
+            size => bytes.div_ceil(size).saturating_sub(1),
+        };
+        per_byte * bytes + separators
+    }
+
+    /**
+    Write the groups of a line with `write` writing each byte in display order
+
+    # Errors
+
+    Returns an error if writing fails
+    */
+    pub(crate) fn write_line<W: Write>(
+        self,
+        line: &[u8],
+        out: &mut W,
+        mut write: impl FnMut(&mut W, u8) -> fmt::Result,
+    ) -> fmt::Result {
+        let size = if self.size == 0 {
+            line.len().max(1)
+        } else {
+            self.size
+        };
+        for (i, group) in line.chunks(size).enumerate() {
+            if i > 0 {
+                out.write_char(' ')?;
+            }
+            if self.little_endian {
+                group.iter().rev().try_for_each(|b| write(out, *b))?;
+            } else {
+                group.iter().try_for_each(|b| write(out, *b))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+Encode bytes to binary representation in groups with optional wrapping; see [`Grouping`]
+
+Like [`encode`](crate::encode), each full line of `columns` bytes ends with a continuation marker
+(`\\`) and a newline.
+
+```
+use bbd_lib::*;
+
+let content = b"Hello, world!\n";
+assert_eq!(
+    encode_grouped(content, Style::Nlbb, 8, Grouping::new(4)),
+    "⢄⠮⢦⢦ ⢾⢢⠂⠿\\\n⢾⠗⢦⠦ ⠊⢐",
+);
+assert_eq!(
+    encode_grouped(content, Style::Nlbb, 8, Grouping::little_endian(4)),
+    "⢦⢦⠮⢄ ⠿⠂⢢⢾\\\n⠦⢦⠗⢾ ⢐⠊",
+);
+```
+
+# Panics
+
+Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+*/
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn encode_grouped(content: &[u8], style: Style, columns: usize, grouping: Grouping) -> String {
+    let wrapping = columns > 0;
+    let columns = if wrapping {
+        columns
+    } else {
+        content.len().max(1)
+    };
+    let mut r = String::with_capacity(4 * content.len());
+    for line in content.chunks(columns) {
+        grouping
+            .write_line(line, &mut r, |r, b| r.write_char(style.encode_byte(b)))
+            .unwrap();
+        if wrapping && line.len() == columns {
+            r.push_str("\\\n");
+        }
+    }
+    r
+}
+
+/**
+Decode grouped binary representation to bytes; see [`encode_grouped`]
+
+Spaces and tabs separate groups; continuation markers (`\\`) and newlines are ignored like in
+[`try_decode`](crate::try_decode). For little-endian display, the bytes within each group are
+reversed back.
+
+```
+use bbd_lib::*;
+
+let content = b"Hello, world!\n";
+for grouping in [Grouping::new(4), Grouping::little_endian(4), Grouping::little_endian(8)] {
+    let encoded = encode_grouped(content, Style::Nrbb, 8, grouping);
+    assert_eq!(decode_grouped(&encoded, Style::Nrbb, grouping.little_endian).unwrap(), content);
+}
+
+let error = decode_grouped("⢄⠮ ⢦x", Style::Nlbb, false).unwrap_err();
+assert_eq!((error.character, error.index, error.column), ('x', 4, 5));
+```
+
+# Errors
+
+Returns an error for the first character that cannot be converted
+*/
+#[cfg(feature = "alloc")]
+pub fn decode_grouped(
+    content: &str,
+    style: Style,
+    little_endian: bool,
+) -> Result<Vec<u8>, DecodeError> {
+    let mut r = Vec::with_capacity(content.len() / 3);
+    let mut start = 0;
+    let (mut line, mut column) = (1, 1);
+    for (index, c) in content.chars().enumerate() {
+        match c {
+            ' ' | '\t' | '\n' => {
+                if little_endian {
+                    r[start..].reverse();
+                }
+                start = r.len();
+                if c == '\n' {
+                    line += 1;
+                    column = 0;
+                }
+            }
+            '\\' => {}
+            _ => r.push(style.try_decode_char(c).map_err(|kind| DecodeError {
+                kind,
+                character: c,
+                index,
+                line,
+                column,
+            })?),
+        }
+        column += 1;
+    }
+    if little_endian {
+        r[start..].reverse();
+    }
+    Ok(r)
+}
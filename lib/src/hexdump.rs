@@ -0,0 +1,230 @@
+use {
+    crate::{Grouping, Palette, Style, encode_into},
+    core::fmt::{self, Write},
+};
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/**
+Dump layout like `xxd` and `hexdump -C` on top of [`encode_into`]
+
+Each line has an offset column, a hex column, the binary representation in the chosen style and an
+ASCII gutter. Each column can be turned off via its field. Without a [`Grouping`], the hex column is
+grouped by 2 bytes like `xxd` and the binary representation is not grouped; with a grouping, both
+are grouped (and reordered for little-endian display) the same way. With a [`Palette`], the bytes
+are colored by class in all columns like `hexyl`.
+
+```
+use bbd_lib::*;
+
+let hexdump = Hexdump::new(Style::Nlbb);
+assert_eq!(
+    hexdump.format(b"Hello, world!\nHow are you?\n"),
+    "\
+00000000:  4865 6c6c 6f2c 2077 6f72 6c64 210a 486f  ⢄⠮⢦⢦⢾⢢⠂⠿⢾⠗⢦⠦⠊⢐⢄⢾  |Hello, world!.Ho|
+00000010:  7720 6172 6520 796f 753f 0a              ⠿⠂⠎⠗⠮⠂⢏⢾⠯⢻⢐       |w are you?.|
+",
+);
+
+let hexdump = Hexdump { offset: false, hex: false, ..Hexdump::new(Style::Nlbb) };
+assert_eq!(hexdump.format(b"Hello\n"), "⢄⠮⢦⢦⢾⢐            |Hello.|\n");
+
+let hexdump = Hexdump { columns: 4, ascii: false, ..Hexdump::new(Style::Nlbb) };
+assert_eq!(hexdump.format(b"Hello\n"), "00000000:  4865 6c6c  ⢄⠮⢦⢦\n00000004:  6f0a       ⢾⢐\n");
+
+let hexdump = Hexdump { grouping: Some(Grouping::little_endian(4)), ..Hexdump::new(Style::Nlbb) };
+assert_eq!(
+    hexdump.format(&[0x78, 0x56, 0x34, 0x12, 0xEF, 0xBE]),
+    "00000000:  12345678 beef                        ⠑⠣⠵⢇ ⣳⣾              |xV4...|\n",
+);
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Hexdump {
+    /// Style of the binary representation
+    pub style: Style,
+
+    /// Bytes per line; 0: one line
+    pub columns: usize,
+
+    /// Offset of the first byte
+    pub start: usize,
+
+    /// Show the offset column
+    pub offset: bool,
+
+    /// Show the hex column
+    pub hex: bool,
+
+    /// Show the binary representation column
+    pub braille: bool,
+
+    /// Show the ASCII gutter
+    pub ascii: bool,
+
+    /// Grouping of the hex and binary representation columns
+    pub grouping: Option<Grouping>,
+
+    /// Colors of the hex, binary representation and ASCII columns
+    pub palette: Option<Palette>,
+}
+
+impl Default for Hexdump {
+    fn default() -> Hexdump {
+        Hexdump::new(Style::default())
+    }
+}
+
+impl Hexdump {
+    /// Create a hexdump layout with 16 bytes per line and all columns using the given style
+    #[must_use]
+    pub fn new(style: Style) -> Hexdump {
+        Hexdump {
+            style,
+            columns: 16,
+            start: 0,
+            offset: true,
+            hex: true,
+            braille: true,
+            ascii: true,
+            grouping: None,
+            palette: None,
+        }
+    }
+
+    /**
+    Format content to lines
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+    */
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn format(&self, content: &[u8]) -> String {
+        let mut r = String::new();
+        self.write(content, &mut r).unwrap();
+        r
+    }
+
+    /**
+    Write content as lines
+
+    # Errors
+
+    Returns an error if writing fails
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the content has a byte greater than 99
+    */
+    pub fn write(&self, content: &[u8], out: &mut impl Write) -> fmt::Result {
+        let columns = if self.columns == 0 {
+            content.len().max(1)
+        } else {
+            self.columns
+        };
+        for (i, line) in content.chunks(columns).enumerate() {
+            self.write_line(self.start + i * columns, line, out)?;
+        }
+        Ok(())
+    }
+
+    /**
+    Write a line of up to `columns` bytes at the given offset, including the newline
+
+    Short lines are padded so that the columns line up with full lines; there is no trailing
+    padding.
+
+    # Errors
+
+    Returns an error if writing fails
+
+    # Panics
+
+    Panics if the style is [`Style::Bcd`] and the line has a byte greater than 99
+    */
+    pub fn write_line(&self, offset: usize, line: &[u8], out: &mut impl Write) -> fmt::Result {
+        let columns = if self.columns == 0 {
+            line.len()
+        } else {
+            self.columns
+        };
+        let mut separator = "";
+        if self.offset {
+            write!(out, "{offset:08x}:")?;
+            separator = "  ";
+        }
+        if self.hex {
+            let grouping = self.grouping.unwrap_or(Grouping::new(2));
+            out.write_str(separator)?;
+            grouping.write_line(line, out, |out, b| {
+                self.paint(b, out, |out| write!(out, "{b:02x}"))
+            })?;
+            if self.braille || self.ascii {
+                pad(
+                    out,
+                    grouping.width(columns, 2) - grouping.width(line.len(), 2),
+                )?;
+            }
+            separator = "  ";
+        }
+        if self.braille {
+            out.write_str(separator)?;
+            if let Some(grouping) = self.grouping {
+                grouping.write_line(line, out, |out, b| {
+                    self.paint(b, out, |out| out.write_char(self.style.encode_byte(b)))
+                })?;
+            } else if let Some(palette) = self.palette {
+                palette.write_runs(line, out, |out, run| {
+                    encode_into(run, self.style, 0, 0, out).map(|_| ())
+                })?;
+            } else {
+                encode_into(line, self.style, 0, 0, out)?;
+            }
+            if self.ascii {
+                let grouping = self.grouping.unwrap_or(Grouping::new(0));
+                pad(
+                    out,
+                    grouping.width(columns, 1) - grouping.width(line.len(), 1),
+                )?;
+            }
+            separator = "  ";
+        }
+        if self.ascii {
+            out.write_str(separator)?;
+            out.write_char('|')?;
+            for b in line {
+                self.paint(*b, out, |out| {
+                    out.write_char(if b.is_ascii_graphic() || *b == b' ' {
+                        char::from(*b)
+                    } else {
+                        '.'
+                    })
+                })?;
+            }
+            out.write_char('|')?;
+        }
+        out.write_char('\n')
+    }
+
+    /// Write a byte with `write` in the color of its class, if any
+    fn paint<W: Write>(
+        &self,
+        b: u8,
+        out: &mut W,
+        mut write: impl FnMut(&mut W) -> fmt::Result,
+    ) -> fmt::Result {
+        match self.palette {
+            Some(palette) => palette.write_runs(&[b], out, |out, _| write(out)),
+            None => write(out),
+        }
+    }
+}
+
+/// Write the given number of spaces
+fn pad(out: &mut impl Write, n: usize) -> fmt::Result {
+    (0..n).try_for_each(|_| out.write_char(' '))
+}
@@ -1,31 +1,126 @@
 #![doc = include_str!("../README.md")]
-
-use std::sync::LazyLock;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "alloc")]
+mod armor;
+#[cfg(feature = "alloc")]
+mod bundle;
+#[cfg(feature = "alloc")]
+mod check;
+mod color;
+#[cfg(feature = "alloc")]
+mod decimal;
+#[cfg(feature = "alloc")]
+mod detect;
+#[cfg(feature = "alloc")]
+mod diff;
+mod encoder;
+#[cfg(feature = "alloc")]
+mod fec;
+mod group;
+mod hexdump;
+#[cfg(feature = "alloc")]
+mod markdown;
+#[cfg(feature = "alloc")]
+mod netpbm;
+#[cfg(feature = "alloc")]
+mod policy;
+#[cfg(feature = "alloc")]
+mod raster;
+#[cfg(feature = "alloc")]
+mod reverse;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "alloc")]
+mod verify;
+
+#[cfg(feature = "alloc")]
+pub use armor::{ARMOR_BEGIN, ARMOR_END, Armor, ArmorError, is_armored};
+#[cfg(feature = "alloc")]
+pub use bundle::{BUNDLE_BEGIN, BUNDLE_END, Bundle, BundleError, Entry, glob_match};
+#[cfg(feature = "alloc")]
+pub use check::{
+    CHECKSUM_SEPARATOR, Checked, LineError, LineErrorKind, decode_checked, encode_checked, line_crc,
+};
+pub use color::{ByteClass, Color, Palette, PaletteError};
+#[cfg(feature = "alloc")]
+pub use decimal::{
+    DecimalError, Integer, PACKED_STYLE, decode_decimals, encode_decimal, pack_decimal,
+    pack_integer, unpack_decimals, unpack_integers,
+};
+#[cfg(feature = "alloc")]
+pub use detect::{Detection, detect_style, style_header};
+#[cfg(feature = "alloc")]
+pub use diff::{Aligned, Alignment, Diff, GAP, MYERS_MAX_EDITS, align};
+pub use encoder::Encoder;
+#[cfg(feature = "alloc")]
+pub use fec::{Corrected, FEC_BLOCK, FecError, decode_fec, encode_fec};
+pub use group::Grouping;
+#[cfg(feature = "alloc")]
+pub use group::{decode_grouped, encode_grouped};
+pub use hexdump::Hexdump;
+#[cfg(feature = "alloc")]
+pub use markdown::{MarkdownError, MarkdownErrorKind, Section, parse_markdown};
+#[cfg(feature = "alloc")]
+pub use netpbm::{Dither, Graymap, NetpbmError};
+#[cfg(feature = "alloc")]
+pub use policy::{Decoded, Policy, decode_with_policy};
+#[cfg(feature = "alloc")]
+pub use raster::{BitOrder, Bitmap, Layout};
+#[cfg(feature = "alloc")]
+pub use reverse::{DumpLine, ReverseError, ReverseErrorKind, parse_dump, reverse_dump};
+#[cfg(feature = "std")]
+pub use reverse::{patch_dump, write_dump};
+#[cfg(feature = "std")]
+pub use stream::{BrailleReader, BrailleWriter};
+#[cfg(feature = "alloc")]
+pub use verify::{VerifyError, VerifyErrorKind, verify};
 
 // Braille dot values given in LSB to MSB order for each "style"
-const NLBB: &[u32; 8] = &[8, 16, 32, 128, 1, 2, 4, 64];
-const NLBT: &[u32; 8] = &[128, 32, 16, 8, 64, 4, 2, 1];
-const NRBB: &[u32; 8] = &[1, 2, 4, 64, 8, 16, 32, 128];
-const NRBT: &[u32; 8] = &[64, 4, 2, 1, 128, 32, 16, 8];
+const DIRECT: [u8; 8] = [1, 2, 4, 8, 16, 32, 64, 128];
+const NLBB: [u8; 8] = [8, 16, 32, 128, 1, 2, 4, 64];
+const NLBT: [u8; 8] = [128, 32, 16, 8, 64, 4, 2, 1];
+const NRBB: [u8; 8] = [1, 2, 4, 64, 8, 16, 32, 128];
+const NRBT: [u8; 8] = [64, 4, 2, 1, 128, 32, 16, 8];
 
 // BCD
-const TENS: &[u8] = &[0x00, 0x40, 0x04, 0x44, 0x02, 0x42, 0x06, 0x46, 0x01, 0x41];
-const ONES: &[u8] = &[0x00, 0x80, 0x20, 0xA0, 0x10, 0x90, 0x30, 0xB0, 0x08, 0x88];
-
-// Encode
-static ENCODE_NLBB: LazyLock<Vec<(u8, u32)>> = LazyLock::new(|| style_encode(*NLBB));
-static ENCODE_NLBT: LazyLock<Vec<(u8, u32)>> = LazyLock::new(|| style_encode(*NLBT));
-static ENCODE_NRBB: LazyLock<Vec<(u8, u32)>> = LazyLock::new(|| style_encode(*NRBB));
-static ENCODE_NRBT: LazyLock<Vec<(u8, u32)>> = LazyLock::new(|| style_encode(*NRBT));
-
-// Decode
-static DECODE_NLBB: LazyLock<Vec<(u8, u8)>> = LazyLock::new(|| style_decode(*NLBB));
-static DECODE_NLBT: LazyLock<Vec<(u8, u8)>> = LazyLock::new(|| style_decode(*NLBT));
-static DECODE_NRBB: LazyLock<Vec<(u8, u8)>> = LazyLock::new(|| style_decode(*NRBB));
-static DECODE_NRBT: LazyLock<Vec<(u8, u8)>> = LazyLock::new(|| style_decode(*NRBT));
+const TENS: [u8; 10] = [0x00, 0x40, 0x04, 0x44, 0x02, 0x42, 0x06, 0x46, 0x01, 0x41];
+const ONES: [u8; 10] = [0x00, 0x80, 0x20, 0xA0, 0x10, 0x90, 0x30, 0xB0, 0x08, 0x88];
+const INVALID_BCD: u8 = 0xFF;
+
+// Encode: Braille dot pattern indexed by byte value
+const ENCODE_DIRECT: [u8; 256] = style_encode(DIRECT);
+const ENCODE_NLBB: [u8; 256] = style_encode(NLBB);
+const ENCODE_NLBT: [u8; 256] = style_encode(NLBT);
+const ENCODE_NRBB: [u8; 256] = style_encode(NRBB);
+const ENCODE_NRBT: [u8; 256] = style_encode(NRBT);
+const ENCODE_BCD: [u8; 256] = bcd_encode();
+
+// Decode: byte value indexed by Braille dot pattern
+const DECODE_DIRECT: [u8; 256] = style_decode(DIRECT);
+const DECODE_NLBB: [u8; 256] = style_decode(NLBB);
+const DECODE_NLBT: [u8; 256] = style_decode(NLBT);
+const DECODE_NRBB: [u8; 256] = style_decode(NRBB);
+const DECODE_NRBT: [u8; 256] = style_decode(NRBT);
+const DECODE_BCD: [u8; 256] = bcd_decode();
+
+// Lookup tables for the fast path
+static TABLES_DIRECT: Tables = Tables::new(ENCODE_DIRECT, DECODE_DIRECT, false);
+static TABLES_NLBB: Tables = Tables::new(ENCODE_NLBB, DECODE_NLBB, false);
+static TABLES_NLBT: Tables = Tables::new(ENCODE_NLBT, DECODE_NLBT, false);
+static TABLES_NRBB: Tables = Tables::new(ENCODE_NRBB, DECODE_NRBB, false);
+static TABLES_NRBT: Tables = Tables::new(ENCODE_NRBT, DECODE_NRBT, false);
+static TABLES_BCD: Tables = Tables::new(ENCODE_BCD, DECODE_BCD, true);
 
 pub type EncodeFn = fn(u8) -> char;
 pub type DecodeFn = fn(char) -> u8;
+pub type TryDecodeFn = fn(char) -> Result<u8, DecodeErrorKind>;
 
 /**
 Translate a [`u8`] to binary representation using the `bcd` encoding
@@ -68,10 +163,7 @@ pub fn encode_bcd(decimal: u8) -> char {
         decimal < 100,
         "Invalid BCD value: {decimal}! Must be in range `0..=99`.",
     );
-    let d = decimal as usize;
-    let tens = d / 10;
-    let ones = d - tens * 10;
-    char::from_u32(0x2800 + u32::from(TENS[tens]) + u32::from(ONES[ones])).unwrap()
+    encode_direct(ENCODE_BCD[usize::from(decimal)])
 }
 
 /**
@@ -100,27 +192,37 @@ assert_eq!(
 
 # Panics
 
-Panics if not able to convert the given BCD `char` to its associated `u8` "value"
+Panics if not able to convert the given BCD `char` to its associated `u8` "value"; see
+[`try_decode_bcd`]
 */
 #[must_use]
 pub fn decode_bcd(c: char) -> u8 {
-    let b = decode_direct(c);
-    let mut decimal: u8 = 0;
-    for (i, tens) in TENS.iter().enumerate().rev() {
-        if b & *tens == *tens {
-            let i = u8::try_from(i).unwrap();
-            decimal += i * 10;
-            break;
-        }
+    match try_decode_bcd(c) {
+        Ok(decimal) => decimal,
+        Err(kind) => panic!("Invalid BCD character `{c}`: {kind}!"),
     }
-    for (i, ones) in ONES.iter().enumerate().rev() {
-        if b & *ones == *ones {
-            let i = u8::try_from(i).unwrap();
-            decimal += i;
-            break;
-        }
+}
+
+/**
+Translate a binary representation to [`u8`] using the `bcd` encoding without panicking
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode_bcd('⣉'), Ok(99));
+assert_eq!(try_decode_bcd('⣿'), Err(DecodeErrorKind::InvalidBcd));
+assert_eq!(try_decode_bcd(' '), Err(DecodeErrorKind::NotBraille));
+```
+
+# Errors
+
+Returns an error if the given `char` is not a Braille pattern or not a valid BCD cell
+*/
+pub fn try_decode_bcd(c: char) -> Result<u8, DecodeErrorKind> {
+    match DECODE_BCD[usize::from(try_decode_direct(c)?)] {
+        INVALID_BCD => Err(DecodeErrorKind::InvalidBcd),
+        decimal => Ok(decimal),
     }
-    decimal
 }
 
 /**
@@ -191,30 +293,49 @@ pub fn decode_direct(c: char) -> u8 {
     u8::try_from(v - 0x2800).unwrap()
 }
 
+/**
+Translate a binary representation to a [`u8`] using the `direct` encoding without panicking
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode_direct('⣿'), Ok(255));
+assert_eq!(try_decode_direct('\r'), Err(DecodeErrorKind::NotBraille));
+```
+
+# Errors
+
+Returns an error if the given `char` is not in the range 0x2800-0x28FF
+*/
+#[allow(clippy::missing_panics_doc)]
+pub fn try_decode_direct(c: char) -> Result<u8, DecodeErrorKind> {
+    let v = u32::from(c);
+    if (0x2800..=0x28FF).contains(&v) {
+        Ok(u8::try_from(v - 0x2800).unwrap())
+    } else {
+        Err(DecodeErrorKind::NotBraille)
+    }
+}
+
 /**
 Core of `encode_{nlbb,nlbt,nrbb,nrbt}` functions
 */
-fn encode_nb(b: u8, values: &[(u8, u32)]) -> char {
-    char::from_u32(values.iter().fold(
-        0x2800,
-        |s, (from, to)| {
-            if b & *from == *from { s + *to } else { s }
-        },
-    ))
-    .unwrap()
+fn encode_nb(b: u8, table: &[u8; 256]) -> char {
+    encode_direct(table[usize::from(b)])
 }
 
 /**
 Core of `decode_{nlbb,nlbt,nrbb,nrbt}` functions
 */
-fn decode_nb(c: char, values: &[(u8, u8)]) -> u8 {
-    let b = decode_direct(c);
-    values.iter().fold(
-        0,
-        |s, (from, to)| {
-            if b & *from == *from { s + *to } else { s }
-        },
-    )
+fn decode_nb(c: char, table: &[u8; 256]) -> u8 {
+    table[usize::from(decode_direct(c))]
+}
+
+/**
+Core of `try_decode_{nlbb,nlbt,nrbb,nrbt}` functions
+*/
+fn try_decode_nb(c: char, table: &[u8; 256]) -> Result<u8, DecodeErrorKind> {
+    Ok(table[usize::from(try_decode_direct(c)?)])
 }
 
 /**
@@ -278,6 +399,24 @@ pub fn decode_nlbb(c: char) -> u8 {
     decode_nb(c, &DECODE_NLBB)
 }
 
+/**
+Translate a binary representation to [`u8`] using the `nlbb` encoding without panicking
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode_nlbb('⢄'), Ok(b'H'));
+assert_eq!(try_decode_nlbb('H'), Err(DecodeErrorKind::NotBraille));
+```
+
+# Errors
+
+Returns an error if the given `char` is not a Braille pattern
+*/
+pub fn try_decode_nlbb(c: char) -> Result<u8, DecodeErrorKind> {
+    try_decode_nb(c, &DECODE_NLBB)
+}
+
 /**
 Translate a [`u8`] to binary representation using the `nlbt` encoding
 
@@ -339,6 +478,24 @@ pub fn decode_nlbt(c: char) -> u8 {
     decode_nb(c, &DECODE_NLBT)
 }
 
+/**
+Translate a binary representation to [`u8`] using the `nlbt` encoding without panicking
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode_nlbt('⠊'), Ok(b'H'));
+assert_eq!(try_decode_nlbt('H'), Err(DecodeErrorKind::NotBraille));
+```
+
+# Errors
+
+Returns an error if the given `char` is not a Braille pattern
+*/
+pub fn try_decode_nlbt(c: char) -> Result<u8, DecodeErrorKind> {
+    try_decode_nb(c, &DECODE_NLBT)
+}
+
 /**
 Translate a [`u8`] to binary representation using the `nrbb` encoding
 
@@ -401,6 +558,24 @@ pub fn decode_nrbb(c: char) -> u8 {
     decode_nb(c, &DECODE_NRBB)
 }
 
+/**
+Translate a binary representation to [`u8`] using the `nrbb` encoding without panicking
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode_nrbb('⡠'), Ok(b'H'));
+assert_eq!(try_decode_nrbb('H'), Err(DecodeErrorKind::NotBraille));
+```
+
+# Errors
+
+Returns an error if the given `char` is not a Braille pattern
+*/
+pub fn try_decode_nrbb(c: char) -> Result<u8, DecodeErrorKind> {
+    try_decode_nb(c, &DECODE_NRBB)
+}
+
 /**
 Translate a [`u8`] to binary representation using the `nrbt` encoding
 
@@ -463,9 +638,31 @@ pub fn decode_nrbt(c: char) -> u8 {
     decode_nb(c, &DECODE_NRBT)
 }
 
+/**
+Translate a binary representation to [`u8`] using the `nrbt` encoding without panicking
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode_nrbt('⠑'), Ok(b'H'));
+assert_eq!(try_decode_nrbt('H'), Err(DecodeErrorKind::NotBraille));
+```
+
+# Errors
+
+Returns an error if the given `char` is not a Braille pattern
+*/
+pub fn try_decode_nrbt(c: char) -> Result<u8, DecodeErrorKind> {
+    try_decode_nb(c, &DECODE_NRBT)
+}
+
 /**
 Encode bytes to binary representation using the given function with optional wrapping
 
+Wrapping continues after `prev_content_length` bytes encoded by previous calls; callers that
+encode in pieces must pass the total length so far, or use an [`Encoder`], which keeps track of
+the wrap position itself.
+
 ```
 use bbd_lib::*;
 
@@ -536,6 +733,7 @@ for (content, convert_byte, result) in cases {
 }
 ```
 */
+#[cfg(feature = "alloc")]
 pub fn encode(
     content: &[u8],
     convert_byte: EncodeFn,
@@ -638,6 +836,7 @@ for (content, convert_byte, result) in cases {
 }
 ```
 */
+#[cfg(feature = "alloc")]
 pub fn decode(content: &str, convert_char: DecodeFn) -> Vec<u8> {
     let mut r = Vec::with_capacity(content.len() / 4);
     for c in content.chars() {
@@ -649,25 +848,959 @@ pub fn decode(content: &str, convert_char: DecodeFn) -> Vec<u8> {
 }
 
 /**
-Process a style definition into a list of from/to conversion values for encoding
+Decode binary representation to bytes using the given function without panicking
+
+Like [`decode`], continuation markers (`\\`) and newlines are ignored, but any other character
+that the given function fails to convert is reported as a [`DecodeError`] with its position.
+
+```
+use bbd_lib::*;
+
+assert_eq!(try_decode("⢄⠮⢦\\\n⢦⢾⢐", try_decode_nlbb), Ok(b"Hello\n".to_vec()));
+
+let error = try_decode("⢄⠮⢦\\\n⢦ ⢾⢐", try_decode_nlbb).unwrap_err();
+assert_eq!(error.kind, DecodeErrorKind::NotBraille);
+assert_eq!(error.character, ' ');
+assert_eq!((error.index, error.line, error.column), (6, 2, 2));
+
+let error = try_decode("⠀⢀\n⣿", try_decode_bcd).unwrap_err();
+assert_eq!(error.kind, DecodeErrorKind::InvalidBcd);
+assert_eq!((error.index, error.line, error.column), (3, 2, 1));
+```
+
+# Errors
+
+Returns an error for the first character that cannot be converted
+*/
+#[cfg(feature = "alloc")]
+pub fn try_decode(content: &str, convert_char: TryDecodeFn) -> Result<Vec<u8>, DecodeError> {
+    try_decode_with(content, convert_char)
+}
+
+/**
+Core of [`try_decode`] and [`decode_str`]
+*/
+#[cfg(feature = "alloc")]
+fn try_decode_with(
+    content: &str,
+    convert_char: impl Fn(char) -> Result<u8, DecodeErrorKind>,
+) -> Result<Vec<u8>, DecodeError> {
+    let mut r = Vec::with_capacity(content.len() / 4);
+    let (mut line, mut column) = (1, 1);
+    for (index, c) in content.chars().enumerate() {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+            continue;
+        }
+        if c != '\\' {
+            r.push(convert_char(c).map_err(|kind| DecodeError {
+                kind,
+                character: c,
+                index,
+                line,
+                column,
+            })?);
+        }
+        column += 1;
+    }
+    Ok(r)
+}
+
+/**
+Reason a character could not be decoded
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeErrorKind {
+    /// Character is not in the Braille Patterns Unicode Block (0x2800-0x28FF)
+    NotBraille,
+
+    /// Braille pattern is not a valid binary coded decimal cell
+    InvalidBcd,
+}
+
+impl core::fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            DecodeErrorKind::NotBraille => "not a Braille pattern",
+            DecodeErrorKind::InvalidBcd => "not a valid BCD cell",
+        })
+    }
+}
+
+/**
+Error returned when a character cannot be decoded
+
+The `index` is the zero-based position of the character in the content; `line` and `column` are
+one-based and count characters.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DecodeError {
+    pub kind: DecodeErrorKind,
+    pub character: char,
+    pub index: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "Invalid character {:?} (U+{:04X}) at line {}, column {} (index {}): {}!",
+            self.character,
+            u32::from(self.character),
+            self.line,
+            self.column,
+            self.index,
+            self.kind,
+        )
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/**
+Binary representation style
+
+Bundles the encode and decode functions of each style with its name and description so that
+consumers can be built from a single list of styles ([`Style::ALL`]).
+
+```
+use bbd_lib::*;
+
+assert_eq!(Style::default(), Style::Nlbb);
+assert_eq!("nrbt".parse::<Style>(), Ok(Style::Nrbt));
+assert_eq!(Style::Bcd.to_string(), "bcd");
+assert!("nope".parse::<Style>().is_err());
+
+for style in Style::ALL {
+    assert_eq!(style.name().parse::<Style>(), Ok(*style));
+    assert!((0..=99).all(|b| style.decode_char(style.encode_byte(b)) == b));
+}
+
+assert_eq!(Style::Nlbb.encode(b"Hello\n", 0, 0), "⢄⠮⢦⢦⢾⢐");
+assert_eq!(Style::Nlbb.decode("⢄⠮⢦⢦⢾⢐"), b"Hello\n");
+```
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// Binary coded decimal of byte values 0-99
+    Bcd,
+
+    /// Direct encoding using the standard Braille dot values
+    Direct,
+
+    /// Most significant nibble left, most significant bit bottom
+    #[default]
+    Nlbb,
+
+    /// Most significant nibble left, most significant bit top
+    Nlbt,
+
+    /// Most significant nibble right, most significant bit bottom
+    Nrbb,
+
+    /// Most significant nibble right, most significant bit top
+    Nrbt,
+
+    /// User-defined permutation of the Braille dots
+    Custom(CustomStyle),
+}
+
+impl Style {
+    /// All built-in styles
+    pub const ALL: &[Style] = &[
+        Style::Bcd,
+        Style::Direct,
+        Style::Nlbb,
+        Style::Nlbt,
+        Style::Nrbb,
+        Style::Nrbt,
+    ];
+
+    /// Name of the style
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Style::Bcd => "bcd",
+            Style::Direct => "direct",
+            Style::Nlbb => "nlbb",
+            Style::Nlbt => "nlbt",
+            Style::Nrbb => "nrbb",
+            Style::Nrbt => "nrbt",
+            Style::Custom(_) => "custom",
+        }
+    }
+
+    /// Description of the style
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Style::Bcd => "Binary coded decimal of byte values 0-99",
+            Style::Direct => "Direct encoding using the standard Braille dot values",
+            Style::Nlbb => "Most significant nibble left column, most significant bit bottom row",
+            Style::Nlbt => "Most significant nibble left column, most significant bit top row",
+            Style::Nrbb => "Most significant nibble right column, most significant bit bottom row",
+            Style::Nrbt => "Most significant nibble right column, most significant bit top row",
+            Style::Custom(_) => "User-defined permutation of the Braille dots",
+        }
+    }
+
+    /**
+    Translate a [`u8`] to binary representation
+
+    # Panics
+
+    Panics under the same conditions as the style's `encode_*` function
+    */
+    #[must_use]
+    pub fn encode_byte(self, b: u8) -> char {
+        match self {
+            Style::Bcd => encode_bcd(b),
+            Style::Direct => encode_direct(b),
+            Style::Nlbb => encode_nlbb(b),
+            Style::Nlbt => encode_nlbt(b),
+            Style::Nrbb => encode_nrbb(b),
+            Style::Nrbt => encode_nrbt(b),
+            Style::Custom(custom) => custom.encode_byte(b),
+        }
+    }
+
+    /**
+    Translate a binary representation to [`u8`]
+
+    # Panics
+
+    Panics under the same conditions as the style's `decode_*` function
+    */
+    #[must_use]
+    pub fn decode_char(self, c: char) -> u8 {
+        match self {
+            Style::Bcd => decode_bcd(c),
+            Style::Direct => decode_direct(c),
+            Style::Nlbb => decode_nlbb(c),
+            Style::Nlbt => decode_nlbt(c),
+            Style::Nrbb => decode_nrbb(c),
+            Style::Nrbt => decode_nrbt(c),
+            Style::Custom(custom) => custom.decode_char(c),
+        }
+    }
+
+    /**
+    Translate a binary representation to [`u8`] without panicking
+
+    # Errors
+
+    Returns an error under the same conditions as the style's `try_decode_*` function
+    */
+    pub fn try_decode_char(self, c: char) -> Result<u8, DecodeErrorKind> {
+        match self {
+            Style::Bcd => try_decode_bcd(c),
+            Style::Direct => try_decode_direct(c),
+            Style::Nlbb => try_decode_nlbb(c),
+            Style::Nlbt => try_decode_nlbt(c),
+            Style::Nrbb => try_decode_nrbb(c),
+            Style::Nrbt => try_decode_nrbt(c),
+            Style::Custom(custom) => custom.try_decode_char(c),
+        }
+    }
+
+    /**
+    Encode bytes to binary representation with optional wrapping using lookup tables; see
+    [`encode`]
+
+    # Panics
+
+    Panics under the same conditions as the style's `encode_*` function
+    */
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn encode(self, content: &[u8], columns: usize, prev_content_length: usize) -> String {
+        let mut r = String::with_capacity(3 * content.len() + content.len() / columns.max(1) * 2);
+        encode_into(content, self, columns, prev_content_length, &mut r).unwrap();
+        r
+    }
+
+    /**
+    Decode binary representation to bytes using lookup tables; see [`decode`]
+
+    # Panics
+
+    Panics if any character cannot be converted; see [`Style::try_decode`]
+    */
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn decode(self, content: &str) -> Vec<u8> {
+        decode_str(content, self).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /**
+    Decode binary representation to bytes without panicking; see [`try_decode`]
+
+    # Errors
+
+    Returns an error for the first character that cannot be converted
+    */
+    #[cfg(feature = "alloc")]
+    pub fn try_decode(self, content: &str) -> Result<Vec<u8>, DecodeError> {
+        decode_str(content, self)
+    }
+
+    /**
+    Call the given function with the lookup tables for the fast path: static for the built-in
+    styles and built for a custom style
+    */
+    pub(crate) fn with_tables<R>(self, f: impl FnOnce(&Tables) -> R) -> R {
+        match self {
+            Style::Bcd => f(&TABLES_BCD),
+            Style::Direct => f(&TABLES_DIRECT),
+            Style::Nlbb => f(&TABLES_NLBB),
+            Style::Nlbt => f(&TABLES_NLBT),
+            Style::Nrbb => f(&TABLES_NRBB),
+            Style::Nrbt => f(&TABLES_NRBT),
+            Style::Custom(custom) => f(&custom.tables()),
+        }
+    }
+
+    /// Copy of the lookup tables for the fast path, for encoders and decoders that keep them
+    pub(crate) fn tables(self) -> Tables {
+        self.with_tables(Tables::clone)
+    }
+}
+
+/**
+Encode bytes to binary representation without wrapping using lookup tables
+
+Writes the UTF-8 encoding of whole chunks of cells at once instead of pushing one [`char`] at a
+time, so it is much faster than [`encode`] for large content.
+
+```
+use bbd_lib::*;
+
+let content = (0..=255).cycle().take(10_000).collect::<Vec<u8>>();
+for style in Style::ALL.iter().skip(1) {
+    assert_eq!(
+        encode_slice(&content, *style),
+        content.iter().map(|b| style.encode_byte(*b)).collect::<String>(),
+    );
+}
+assert_eq!(encode_slice(b"Hello\n", Style::Nlbb), "⢄⠮⢦⢦⢾⢐");
+```
+
+# Panics
+
+Panics under the same conditions as the style's `encode_*` function
+*/
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn encode_slice(content: &[u8], style: Style) -> String {
+    style.encode(content, 0, 0)
+}
+
+/**
+Decode binary representation to bytes using lookup tables
+
+Continuation markers (`\\`) and newlines are ignored. Braille patterns are translated straight
+from their UTF-8 encoding, so it is much faster than [`try_decode`] for large content.
+
+```
+use bbd_lib::*;
+
+let content = (0..=255).cycle().take(10_000).collect::<Vec<u8>>();
+for style in Style::ALL.iter().skip(1) {
+    assert_eq!(decode_str(&style.encode(&content, 64, 0), *style), Ok(content.clone()));
+}
+assert_eq!(decode_str("⢄⠮⢦\\\n⢦⢾⢐", Style::Nlbb), Ok(b"Hello\n".to_vec()));
+
+let error = decode_str("⢄⠮⢦\\\n⢦ ⢾⢐", Style::Nlbb).unwrap_err();
+assert_eq!((error.character, error.line, error.column), (' ', 2, 2));
+```
+
+# Errors
+
+Returns an error for the first character that cannot be converted
+*/
+#[cfg(feature = "alloc")]
+pub fn decode_str(content: &str, style: Style) -> Result<Vec<u8>, DecodeError> {
+    let mut r = Vec::new();
+    match style.with_tables(|tables| tables.decode_to(content, &mut r)) {
+        Ok(()) => Ok(r),
+        Err(offset) => Err(decode_error(content, offset, style)),
+    }
+}
+
+/**
+Encode bytes to binary representation with optional wrapping, appending to the given [`String`]
+or other [`core::fmt::Write`] without allocating
+
+The `columns` and `prev_content_length` arguments have the same meaning as for [`encode`]. The
+returned [`Progress`] reports the number of bytes consumed (always all of the content) and the
+number of UTF-8 bytes produced.
+
+The lookup tables of the built-in styles are static, so many small payloads cost no more than one
+large payload. The tables of a [`CustomStyle`] are built on each call; an [`Encoder`] builds them
+once.
+
+```
+use bbd_lib::*;
+
+let mut buffer = String::new();
+for payload in [&b"Hello\n"[..], b"world\n"] {
+    buffer.clear();
+    let progress = encode_into(payload, Style::Nlbb, 4, 0, &mut buffer).unwrap();
+    assert_eq!(progress, Progress { consumed: 6, produced: buffer.len() });
+}
+assert_eq!(buffer, encode(b"world\n", encode_nlbb, 4, 0));
+assert_eq!(buffer.len(), 20);
+```
+
+# Errors
+
+Returns an error if writing to the output fails
+
+# Panics
+
+Panics under the same conditions as the style's `encode_*` function
+*/
+pub fn encode_into(
+    content: &[u8],
+    style: Style,
+    columns: usize,
+    prev_content_length: usize,
+    out: &mut impl core::fmt::Write,
+) -> Result<Progress, core::fmt::Error> {
+    let mut column = if columns > 0 {
+        prev_content_length % columns
+    } else {
+        0
+    };
+    let markers = (column + content.len()).checked_div(columns).unwrap_or(0);
+    style.with_tables(|tables| tables.encode_to(content, columns, &mut column, out))?;
+    Ok(Progress {
+        consumed: content.len(),
+        produced: 3 * content.len() + 2 * markers,
+    })
+}
+
+/**
+Decode binary representation to bytes in the given buffer without allocating
+
+Continuation markers (`\\`) and newlines are ignored. Decoding stops at the end of the content
+or when the buffer is full. The returned [`Progress`] reports the number of bytes of the content
+consumed, so the rest can be decoded with `&content[progress.consumed..]`, and the number of bytes
+produced in the buffer.
+
+Like [`encode_into`], it uses the static lookup tables of the built-in styles.
+
+```
+use bbd_lib::*;
+
+let content = "⢄⠮⢦\\\n⢦⢾⢐";
+let mut buffer = [0; 4];
+
+let progress = decode_into(content, Style::Nlbb, &mut buffer).unwrap();
+assert_eq!(progress, Progress { consumed: 14, produced: 4 });
+assert_eq!(&buffer, b"Hell");
+
+let progress = decode_into(&content[progress.consumed..], Style::Nlbb, &mut buffer).unwrap();
+assert_eq!(progress, Progress { consumed: 6, produced: 2 });
+assert_eq!(&buffer[..2], b"o\n");
+
+let error = decode_into("⢄⠮ ⢦", Style::Nlbb, &mut buffer).unwrap_err();
+assert_eq!((error.character, error.index), (' ', 2));
+```
+
+# Errors
+
+Returns an error for the first character that cannot be converted
+*/
+pub fn decode_into(content: &str, style: Style, out: &mut [u8]) -> Result<Progress, DecodeError> {
+    style
+        .with_tables(|tables| tables.decode_into(content, out))
+        .map_err(|progress| decode_error(content, progress.consumed, style))
+}
+
+/**
+Amount of input consumed and output produced by [`encode_into`] and [`decode_into`]
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Progress {
+    /// Number of bytes of input consumed
+    pub consumed: usize,
+
+    /// Number of bytes of output produced
+    pub produced: usize,
+}
+
+/// Maximum number of bytes encoded at once by the fast path
+const CHUNK: usize = 1024;
+
+/**
+Lookup tables for the fast path
+*/
+#[derive(Clone, Copy)]
+pub(crate) struct Tables {
+    /// UTF-8 encoded Braille pattern indexed by byte value
+    encode: [[u8; 3]; 256],
+
+    /// Byte value indexed by Braille dot pattern
+    decode: [u8; 256],
+
+    /// Only byte values 0-99 and their patterns are valid
+    bcd: bool,
+}
+
+impl Tables {
+    /**
+    Build the tables from the Braille dot patterns indexed by byte value and the byte values
+    indexed by Braille dot pattern
+    */
+    const fn new(encode: [u8; 256], decode: [u8; 256], bcd: bool) -> Tables {
+        let mut utf8 = [[0; 3]; 256];
+        let mut b = 0;
+        while b < 256 {
+            // U+2800-U+28FF is encoded as 0xE2 0xA0-0xA3 0x80-0xBF
+            utf8[b] = [0xE2, 0xA0 | (encode[b] >> 6), 0x80 | (encode[b] & 0x3F)];
+            b += 1;
+        }
+        Tables {
+            encode: utf8,
+            decode,
+            bcd,
+        }
+    }
+
+    /**
+    Encode bytes to binary representation with optional wrapping starting at the given column,
+    which is updated
+    */
+    pub(crate) fn encode_to(
+        &self,
+        content: &[u8],
+        columns: usize,
+        column: &mut usize,
+        out: &mut impl core::fmt::Write,
+    ) -> core::fmt::Result {
+        let mut buffer = [0; 3 * CHUNK];
+        let mut rest = content;
+        while !rest.is_empty() {
+            let n = if columns > 0 {
+                (columns - *column).min(CHUNK)
+            } else {
+                CHUNK
+            };
+            let (chunk, tail) = rest.split_at(n.min(rest.len()));
+            out.write_str(self.encode_chunk(chunk, &mut buffer))?;
+            if columns > 0 {
+                *column += chunk.len();
+                if *column >= columns {
+                    out.write_str("\\\n")?;
+                    *column = 0;
+                }
+            }
+            rest = tail;
+        }
+        Ok(())
+    }
+
+    /**
+    First byte of the content that the `bcd` encoding cannot represent, if these are its tables
+    */
+    pub(crate) fn invalid_bcd(&self, content: &[u8]) -> Option<u8> {
+        if self.bcd {
+            content.iter().copied().find(|b| *b > 99)
+        } else {
+            None
+        }
+    }
+
+    /**
+    Encode a chunk of at most [`CHUNK`] bytes to binary representation in the given buffer
+    */
+    fn encode_chunk<'a>(&self, chunk: &[u8], buffer: &'a mut [u8; 3 * CHUNK]) -> &'a str {
+        if let Some(decimal) = self.invalid_bcd(chunk) {
+            panic!("Invalid BCD value: {decimal}! Must be in range `0..=99`.");
+        }
+        let buffer = &mut buffer[..3 * chunk.len()];
+        for (cell, b) in buffer.chunks_exact_mut(3).zip(chunk) {
+            cell.copy_from_slice(&self.encode[usize::from(*b)]);
+        }
+        core::str::from_utf8(buffer).unwrap()
+    }
+
+    /**
+    Decode binary representation to bytes until the end of the content or the output is full
+
+    Returns an error with the progress up to the first character that cannot be converted.
+    */
+    fn decode_into(&self, content: &str, out: &mut [u8]) -> Result<Progress, Progress> {
+        let bytes = content.as_bytes();
+        let (mut i, mut n) = (0, 0);
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' | b'\n' => i += 1,
+                // U+2800-U+28FF is encoded as 0xE2 0xA0-0xA3 0x80-0xBF
+                0xE2 if bytes.get(i + 1).is_some_and(|b| b & 0xFC == 0xA0) => {
+                    let dots = ((bytes[i + 1] & 0x03) << 6) | (bytes[i + 2] & 0x3F);
+                    let b = self.decode[usize::from(dots)];
+                    if self.bcd && b == INVALID_BCD {
+                        break;
+                    }
+                    let Some(slot) = out.get_mut(n) else {
+                        return Ok(Progress {
+                            consumed: i,
+                            produced: n,
+                        });
+                    };
+                    *slot = b;
+                    n += 1;
+                    i += 3;
+                }
+                _ => break,
+            }
+        }
+        let progress = Progress {
+            consumed: i,
+            produced: n,
+        };
+        if i < bytes.len() {
+            Err(progress)
+        } else {
+            Ok(progress)
+        }
+    }
+
+    /**
+    Decode binary representation to bytes appended to the given vector
+
+    Returns an error with the byte offset of the first character that cannot be converted; the
+    bytes decoded before it are still appended.
+    */
+    #[cfg(feature = "alloc")]
+    pub(crate) fn decode_to(&self, content: &str, out: &mut Vec<u8>) -> Result<(), usize> {
+        // Every Braille pattern is 3 bytes of UTF-8
+        let start = out.len();
+        out.resize(start + content.len() / 3, 0);
+        let result = self.decode_into(content, &mut out[start..]);
+        let (Ok(progress) | Err(progress)) = result;
+        out.truncate(start + progress.produced);
+        result.map(|_| ()).map_err(|progress| progress.consumed)
+    }
+}
+
+/**
+Describe the character at the given byte offset, which cannot be converted, as a [`DecodeError`]
+*/
+pub(crate) fn decode_error(content: &str, offset: usize, style: Style) -> DecodeError {
+    let (mut index, mut line, mut column) = (0, 1, 1);
+    for c in content[..offset].chars() {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+        index += 1;
+    }
+    let character = content[offset..].chars().next().unwrap();
+    DecodeError {
+        kind: style
+            .try_decode_char(character)
+            .err()
+            .unwrap_or(DecodeErrorKind::NotBraille),
+        character,
+        index,
+        line,
+        column,
+    }
+}
+
+impl core::fmt::Display for Style {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Style::Custom(custom) => write!(f, "{custom}"),
+            style => f.write_str(style.name()),
+        }
+    }
+}
+
+impl core::str::FromStr for Style {
+    type Err = StyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("custom:") {
+            return Ok(Style::Custom(s.parse()?));
+        }
+        Style::ALL
+            .iter()
+            .find(|style| style.name() == s)
+            .copied()
+            .ok_or(StyleError::Unknown)
+    }
+}
+
+/**
+User-defined style given as a permutation of the 8 Braille dots
+
+The permutation is given as the Braille dot value for each bit of a byte, from the least
+significant bit to the most significant bit, like the tables behind the built-in nibble styles.
+It can also be given as Braille dot numbers (1-8), where dot number `n` has the value
+`1 << (n - 1)`.
+
+Braille dot numbers and values:
+
+Left | Right
+---|---
+1 (1) | 4 (8)
+2 (2) | 5 (16)
+3 (4) | 6 (32)
+7 (64) | 8 (128)
+
+```
+use bbd_lib::*;
+
+// Same as `nlbb`
+let custom = CustomStyle::new([8, 16, 32, 128, 1, 2, 4, 64]).unwrap();
+assert_eq!(CustomStyle::from_dot_numbers([4, 5, 6, 8, 1, 2, 3, 7]), Ok(custom));
+assert_eq!(
+    (0..=255).map(|b| custom.encode_byte(b)).collect::<String>(),
+    (0..=255).map(encode_nlbb).collect::<String>(),
+);
+assert!((0..=255).all(|b| custom.decode_char(custom.encode_byte(b)) == b));
+
+// Spec strings accept either form and display as dot values
+let style = "custom:4,5,6,8,1,2,3,7".parse::<Style>().unwrap();
+assert_eq!(style, Style::Custom(custom));
+assert_eq!(style.to_string(), "custom:8,16,32,128,1,2,4,64");
+assert_eq!(style.encode(b"Hello\n", 0, 0), "⢄⠮⢦⢦⢾⢐");
+
+// Not a bijection
+assert_eq!(CustomStyle::new([1, 1, 4, 8, 16, 32, 64, 128]), Err(StyleError::NotPermutation));
+assert!("custom:1,2,3".parse::<Style>().is_err());
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CustomStyle {
+    dots: [u8; 8],
+}
+
+impl CustomStyle {
+    /**
+    Create a custom style from the Braille dot values for each bit from LSB to MSB
+
+    # Errors
+
+    Returns an error if the dot values are not a permutation of the 8 Braille dots
+    */
+    pub fn new(dots: [u8; 8]) -> Result<Self, StyleError> {
+        let all = dots.iter().fold(0, |s, dot| s | dot);
+        if all == 0xFF && dots.iter().all(|dot| dot.is_power_of_two()) {
+            Ok(CustomStyle { dots })
+        } else {
+            Err(StyleError::NotPermutation)
+        }
+    }
+
+    /**
+    Create a custom style from the Braille dot numbers (1-8) for each bit from LSB to MSB
+
+    # Errors
+
+    Returns an error if the dot numbers are not a permutation of 1-8
+    */
+    pub fn from_dot_numbers(numbers: [u8; 8]) -> Result<Self, StyleError> {
+        if numbers.iter().any(|n| !(1..=8).contains(n)) {
+            return Err(StyleError::NotPermutation);
+        }
+        CustomStyle::new(numbers.map(|n| 1 << (n - 1)))
+    }
+
+    /// Braille dot values for each bit from LSB to MSB
+    #[must_use]
+    pub fn dots(self) -> [u8; 8] {
+        self.dots
+    }
+
+    /// Translate a [`u8`] to binary representation
+    #[allow(clippy::missing_panics_doc)]
+    #[must_use]
+    pub fn encode_byte(self, b: u8) -> char {
+        let dots = self
+            .dots
+            .iter()
+            .enumerate()
+            .fold(0, |s, (i, dot)| if b & (1 << i) != 0 { s | dot } else { s });
+        encode_direct(dots)
+    }
+
+    /**
+    Translate a binary representation to [`u8`]
+
+    # Panics
+
+    Panics if given `char` is not in the range 0x2800-0x28FF
+    */
+    #[must_use]
+    pub fn decode_char(self, c: char) -> u8 {
+        self.decode_dots(decode_direct(c))
+    }
+
+    /**
+    Translate a binary representation to [`u8`] without panicking
+
+    # Errors
+
+    Returns an error if the given `char` is not a Braille pattern
+    */
+    pub fn try_decode_char(self, c: char) -> Result<u8, DecodeErrorKind> {
+        Ok(self.decode_dots(try_decode_direct(c)?))
+    }
+
+    /// Translate the dots of a Braille pattern to [`u8`]
+    fn decode_dots(self, dots: u8) -> u8 {
+        self.dots.iter().enumerate().fold(
+            0,
+            |s, (i, dot)| if dots & dot != 0 { s | (1 << i) } else { s },
+        )
+    }
+
+    /// Lookup tables for the fast path
+    fn tables(self) -> Tables {
+        Tables::new(style_encode(self.dots), style_decode(self.dots), false)
+    }
+}
+
+impl core::fmt::Display for CustomStyle {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("custom:")?;
+        for (i, dot) in self.dots.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{dot}")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for CustomStyle {
+    type Err = StyleError;
+
+    /// Parse `custom:` followed by 8 comma-separated dot values or dot numbers
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = [0; 8];
+        let mut n = 0;
+        for value in s
+            .strip_prefix("custom:")
+            .ok_or(StyleError::InvalidSpec)?
+            .split(',')
+        {
+            *values.get_mut(n).ok_or(StyleError::InvalidSpec)? =
+                value.trim().parse().map_err(|_| StyleError::InvalidSpec)?;
+            n += 1;
+        }
+        if n < 8 {
+            return Err(StyleError::InvalidSpec);
+        }
+        if values.iter().all(|v| (1..=8).contains(v)) {
+            CustomStyle::from_dot_numbers(values)
+        } else {
+            CustomStyle::new(values)
+        }
+    }
+}
+
+/**
+Error returned when parsing or creating a [`Style`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StyleError {
+    /// Unknown style name
+    Unknown,
+
+    /// Custom style spec is not `custom:` followed by 8 comma-separated numbers
+    InvalidSpec,
+
+    /// Custom style dots are not a permutation of the 8 Braille dots
+    NotPermutation,
+}
+
+impl core::fmt::Display for StyleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            StyleError::Unknown => f.write_str("Invalid style!"),
+            StyleError::InvalidSpec => f.write_str(
+                "Invalid custom style! Must be `custom:` followed by 8 comma-separated dot values \
+                or dot numbers.",
+            ),
+            StyleError::NotPermutation => {
+                f.write_str("Custom style dots are not a permutation of the 8 Braille dots!")
+            }
+        }
+    }
+}
+
+impl core::error::Error for StyleError {}
+
+/**
+Process a style definition into a table of Braille dot patterns indexed by byte value
+*/
+const fn style_encode(values: [u8; 8]) -> [u8; 256] {
+    let mut r = [0; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut i = 0;
+        while i < 8 {
+            if b & (1 << i) != 0 {
+                r[b] |= values[i];
+            }
+            i += 1;
+        }
+        b += 1;
+    }
+    r
+}
+
+/**
+Process a style definition into a table of byte values indexed by Braille dot pattern
 */
-fn style_encode(values: [u32; 8]) -> Vec<(u8, u32)> {
-    values
-        .iter()
-        .copied()
-        .enumerate()
-        .map(|(i, v)| (1 << i, v))
-        .collect()
+#[allow(clippy::cast_possible_truncation)]
+const fn style_decode(values: [u8; 8]) -> [u8; 256] {
+    let encode = style_encode(values);
+    let mut r = [0; 256];
+    let mut b = 0;
+    while b < 256 {
+        r[encode[b] as usize] = b as u8;
+        b += 1;
+    }
+    r
 }
 
 /**
-Process a style definition into a list of from/to conversion values for decoding
+Table of BCD Braille dot patterns indexed by byte value; values above 99 are not used
 */
-fn style_decode(values: [u32; 8]) -> Vec<(u8, u8)> {
-    values
-        .iter()
-        .copied()
-        .enumerate()
-        .map(|(i, v)| (u8::try_from(v).unwrap(), 1 << i))
-        .collect()
+const fn bcd_encode() -> [u8; 256] {
+    let mut r = [0; 256];
+    let mut d = 0;
+    while d < 100 {
+        r[d] = TENS[d / 10] | ONES[d % 10];
+        d += 1;
+    }
+    r
+}
+
+/**
+Table of byte values indexed by BCD Braille dot pattern; invalid patterns are [`INVALID_BCD`]
+*/
+#[allow(clippy::cast_possible_truncation)]
+const fn bcd_decode() -> [u8; 256] {
+    let encode = bcd_encode();
+    let mut r = [INVALID_BCD; 256];
+    let mut d = 0;
+    while d < 100 {
+        r[encode[d] as usize] = d as u8;
+        d += 1;
+    }
+    r
 }
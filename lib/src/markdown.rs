@@ -0,0 +1,142 @@
+use alloc::vec::Vec;
+
+/// Fence line around the body of a section
+const FENCE: &str = "```";
+
+/**
+Section of a Markdown document written with `bbd -m`: a backticked name line ending in a colon and
+a fenced block
+
+```
+use bbd_lib::*;
+
+let document = "`a.bin`:\n\n```\n⢄⠮⢦\\\n⢦⢾⢐\n```\n\n`b.bin`:\n\n```\n```\n\n";
+let sections = parse_markdown(document).unwrap();
+assert_eq!(sections, [
+    Section { name: "a.bin", body: "⢄⠮⢦\\\n⢦⢾⢐", line: 4 },
+    Section { name: "b.bin", body: "", line: 11 },
+]);
+assert_eq!(Style::Nlbb.try_decode(sections[0].body).unwrap(), b"Hello\n");
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Section<'a> {
+    /// Name between the backticks
+    pub name: &'a str,
+
+    /// Lines between the fences without the final newline
+    pub body: &'a str,
+
+    /// One-based line number of the first line of the body
+    pub line: usize,
+}
+
+/**
+Parse a Markdown document written with `bbd -m` into its sections
+
+Blank lines between the name line and the fences and between sections are optional, the opening
+fence may have an info string, and carriage returns before newlines are ignored outside the bodies.
+
+```
+use bbd_lib::*;
+
+let error = parse_markdown("`a.bin`:\n\n```\n⠁\n").unwrap_err();
+assert_eq!(error, MarkdownError { line: 4, kind: MarkdownErrorKind::Unterminated });
+
+let error = parse_markdown("# Dump\n").unwrap_err();
+assert_eq!(error, MarkdownError { line: 1, kind: MarkdownErrorKind::ExpectedName });
+```
+
+# Errors
+
+Returns an error for the first line that does not follow the format
+*/
+pub fn parse_markdown(content: &str) -> Result<Vec<Section<'_>>, MarkdownError> {
+    let error = |line, kind| MarkdownError { line, kind };
+    let mut lines = content
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .zip(1..)
+        .map(|((offset, line), number)| (number, offset, line))
+        .filter(|(_, _, line)| !line.trim_end().is_empty());
+    let mut r = Vec::new();
+    while let Some((number, _, line)) = lines.next() {
+        let name = line
+            .trim_end()
+            .strip_prefix('`')
+            .and_then(|name| name.strip_suffix("`:"))
+            .ok_or(error(number, MarkdownErrorKind::ExpectedName))?;
+        let (number, start, line) = lines
+            .next()
+            .ok_or(error(number + 1, MarkdownErrorKind::ExpectedFence))?;
+        if !line.starts_with(FENCE) {
+            return Err(error(number, MarkdownErrorKind::ExpectedFence));
+        }
+        let start = start + line.len();
+        let mut body = None;
+        let mut last = number;
+        for (number, offset, line) in lines.by_ref() {
+            if line.trim_end() == FENCE {
+                body = Some(&content[start..offset]);
+                break;
+            }
+            last = number;
+        }
+        let body = body.ok_or(error(last, MarkdownErrorKind::Unterminated))?;
+        r.push(Section {
+            name,
+            body: body.strip_suffix('\n').unwrap_or(body),
+            line: number + 1,
+        });
+    }
+    Ok(r)
+}
+
+/**
+Error returned when parsing a Markdown document; see [`parse_markdown`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MarkdownError {
+    /// One-based line number
+    pub line: usize,
+
+    /// Reason
+    pub kind: MarkdownErrorKind,
+}
+
+impl core::fmt::Display for MarkdownError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.kind)
+    }
+}
+
+impl core::error::Error for MarkdownError {}
+
+/**
+Reason a Markdown document does not parse
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarkdownErrorKind {
+    /// Line is not a backticked name followed by a colon
+    ExpectedName,
+
+    /// Name line is not followed by an opening fence
+    ExpectedFence,
+
+    /// Fenced block has no closing fence
+    Unterminated,
+}
+
+impl core::fmt::Display for MarkdownErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            MarkdownErrorKind::ExpectedName => "Expected a backticked name followed by a colon!",
+            MarkdownErrorKind::ExpectedFence => "Expected an opening fence!",
+            MarkdownErrorKind::Unterminated => "Missing closing fence!",
+        })
+    }
+}
@@ -0,0 +1,421 @@
+use {
+    crate::Bitmap,
+    alloc::{format, vec, vec::Vec},
+};
+
+/**
+Grayscale image read from a Netpbm file, to be dithered to a [`Bitmap`]
+
+Pixels are 0 (black) to 255 (white). Color images are converted to their luminance.
+
+```
+use bbd_lib::*;
+
+// 4x4 gradient from black to white
+let pgm = b"P2\n# gradient\n4 4\n3\n0 1 2 3\n0 1 2 3\n0 1 2 3\n0 1 2 3\n";
+let image = Graymap::from_netpbm(pgm).unwrap();
+assert_eq!((image.width(), image.height()), (4, 4));
+assert_eq!(image.get(1, 0), 85);
+
+assert_eq!(image.dither(Dither::Threshold(128)).to_string(), "⣿⠀\n");
+assert_eq!(image.dither(Dither::Ordered).to_string(), "⣯⡂\n");
+
+// Black and white images round-trip through Braille text
+let bitmap = image.dither(Dither::FloydSteinberg);
+let pbm = bitmap.to_pbm();
+assert!(pbm.starts_with(b"P4\n4 4\n"));
+let image = Graymap::from_netpbm(&pbm).unwrap();
+assert_eq!(image.dither(Dither::Threshold(128)), bitmap);
+assert_eq!(Bitmap::from_braille(&bitmap.to_string()).unwrap(), bitmap);
+```
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Graymap {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Graymap {
+    /**
+    Read a Netpbm image: plain (`P1`-`P3`) or raw (`P4`-`P6`) PBM, PGM or PPM
+
+    ```
+    use bbd_lib::*;
+
+    assert_eq!(Graymap::from_netpbm(b"P4\n0 5\n"), Err(NetpbmError::InvalidHeader));
+    assert_eq!(Graymap::from_netpbm(b"P2 3 0 255\n"), Err(NetpbmError::InvalidHeader));
+    ```
+
+    # Errors
+
+    Returns an error if the content is not a supported Netpbm image, has a zero width or height,
+    or is truncated
+    */
+    pub fn from_netpbm(content: &[u8]) -> Result<Graymap, NetpbmError> {
+        let mut parser = Parser { content, pos: 0 };
+        let format = match parser.token() {
+            Some(b"P1") => 1,
+            Some(b"P2") => 2,
+            Some(b"P3") => 3,
+            Some(b"P4") => 4,
+            Some(b"P5") => 5,
+            Some(b"P6") => 6,
+            _ => return Err(NetpbmError::UnsupportedFormat),
+        };
+        let mut header = || parser.number().map_err(|_| NetpbmError::InvalidHeader);
+        let width = header()?;
+        let height = header()?;
+        if width == 0 || height == 0 {
+            return Err(NetpbmError::InvalidHeader);
+        }
+        let maxval = if format == 1 || format == 4 {
+            1
+        } else {
+            header()?
+        };
+        if !(1..=65535).contains(&maxval) {
+            return Err(NetpbmError::InvalidHeader);
+        }
+        let pixels = width
+            .checked_mul(height)
+            .ok_or(NetpbmError::InvalidHeader)?;
+        if format >= 4 {
+            // Single whitespace character before the raster
+            parser.pos += 1;
+        }
+
+        // Scale a sample to 0-255
+        let scale = |sample: usize| -> Result<u8, NetpbmError> {
+            if sample > maxval {
+                return Err(NetpbmError::InvalidSample);
+            }
+            u8::try_from(sample * 255 / maxval).map_err(|_| NetpbmError::InvalidSample)
+        };
+        let mut r = Graymap {
+            width,
+            height,
+            pixels: Vec::with_capacity(pixels.min(content.len() * 8)),
+        };
+        match format {
+            1 => {
+                // Plain PBM samples need no whitespace between them
+                for _ in 0..pixels {
+                    parser.skip();
+                    let black = match parser.content.get(parser.pos) {
+                        Some(b'0') => false,
+                        Some(b'1') => true,
+                        Some(_) => return Err(NetpbmError::InvalidSample),
+                        None => return Err(NetpbmError::Truncated),
+                    };
+                    parser.pos += 1;
+                    r.pixels.push(if black { 0 } else { 255 });
+                }
+            }
+            2 => {
+                for _ in 0..pixels {
+                    r.pixels.push(scale(parser.number()?)?);
+                }
+            }
+            3 => {
+                for _ in 0..pixels {
+                    let rgb = [parser.number()?, parser.number()?, parser.number()?];
+                    r.pixels.push(luminance(rgb.map(scale))?);
+                }
+            }
+            4 => {
+                let stride = width.div_ceil(8);
+                let raster = parser.raster(stride.checked_mul(height))?;
+                for row in raster.chunks(stride) {
+                    for x in 0..width {
+                        let black = row[x / 8] & (0x80 >> (x % 8)) != 0;
+                        r.pixels.push(if black { 0 } else { 255 });
+                    }
+                }
+            }
+            _ => {
+                let channels = if format == 5 { 1 } else { 3 };
+                let bytes = if maxval > 255 { 2 } else { 1 };
+                let raster = parser.raster(pixels.checked_mul(channels * bytes))?;
+                let samples = raster
+                    .chunks(bytes)
+                    .map(|s| s.iter().fold(0, |r, b| (r << 8) | usize::from(*b)));
+                if channels == 1 {
+                    for sample in samples {
+                        r.pixels.push(scale(sample)?);
+                    }
+                } else {
+                    let samples = samples.collect::<Vec<usize>>();
+                    for rgb in samples.chunks(3) {
+                        r.pixels
+                            .push(luminance([rgb[0], rgb[1], rgb[2]].map(scale))?);
+                    }
+                }
+            }
+        }
+        Ok(r)
+    }
+
+    /// Width in pixels
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /**
+    Pixel at the given position
+
+    # Panics
+
+    Panics if the position is outside the image
+    */
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(
+            x < self.width && y < self.height,
+            "Pixel {x},{y} is outside the {}x{} image!",
+            self.width,
+            self.height,
+        );
+        self.pixels[y * self.width + x]
+    }
+
+    /**
+    Resize to the given width keeping the aspect ratio
+
+    Each pixel is the average of the pixels it covers when shrinking, or the nearest pixel when
+    growing.
+
+    ```
+    use bbd_lib::*;
+
+    let image = Graymap::from_netpbm(b"P2 4 2 255 0 0 255 255 0 0 255 255").unwrap();
+    let half = image.resize(2);
+    assert_eq!((half.width(), half.height()), (2, 1));
+    assert_eq!((half.get(0, 0), half.get(1, 0)), (0, 255));
+    ```
+    */
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn resize(&self, width: usize) -> Graymap {
+        if self.width == 0 || width == 0 {
+            return Graymap {
+                width,
+                height: 0,
+                pixels: vec![],
+            };
+        }
+        let height = (self.height * width).div_ceil(self.width);
+        // Range of source pixels covered by destination pixel `i` of `n`
+        let span = |i: usize, n: usize, source: usize| {
+            let start = i * source / n;
+            start..((i + 1) * source / n).max(start + 1)
+        };
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let rows = span(y, height, self.height);
+            for x in 0..width {
+                let columns = span(x, width, self.width);
+                let count = rows.len() * columns.len();
+                let sum = rows
+                    .clone()
+                    .flat_map(|sy| columns.clone().map(move |sx| (sx, sy)))
+                    .map(|(sx, sy)| usize::from(self.pixels[sy * self.width + sx]))
+                    .sum::<usize>();
+                pixels.push(u8::try_from(sum / count).unwrap());
+            }
+        }
+        Graymap {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /**
+    Convert to a bitmap; dark pixels become dots
+    */
+    #[must_use]
+    pub fn dither(&self, dither: Dither) -> Bitmap {
+        let mut r = Bitmap::new(self.width, self.height);
+        match dither {
+            Dither::Threshold(threshold) => {
+                for (i, pixel) in self.pixels.iter().enumerate() {
+                    r.set(i % self.width, i / self.width, *pixel < threshold);
+                }
+            }
+            Dither::Ordered => {
+                for (i, pixel) in self.pixels.iter().enumerate() {
+                    let (x, y) = (i % self.width, i / self.width);
+                    let threshold = 16 * u16::from(BAYER[y % 4][x % 4]) + 8;
+                    r.set(x, y, u16::from(*pixel) < threshold);
+                }
+            }
+            Dither::FloydSteinberg => {
+                // Error diffused into the current and next rows
+                let mut errors = vec![0_i32; 2 * (self.width + 2)];
+                for y in 0..self.height {
+                    let (current, next) = errors.split_at_mut(self.width + 2);
+                    for x in 0..self.width {
+                        let value =
+                            i32::from(self.pixels[y * self.width + x]) + current[x + 1] / 16;
+                        let black = value < 128;
+                        r.set(x, y, black);
+                        let error = if black { value } else { value - 255 };
+                        current[x + 2] += error * 7;
+                        next[x] += error * 3;
+                        next[x + 1] += error * 5;
+                        next[x + 2] += error;
+                    }
+                    current.fill(0);
+                    errors.rotate_left(self.width + 2);
+                }
+            }
+        }
+        r
+    }
+}
+
+impl Bitmap {
+    /**
+    Write as a raw (`P4`) PBM image; dots are black
+    */
+    #[must_use]
+    pub fn to_pbm(&self) -> Vec<u8> {
+        let stride = self.width().div_ceil(8);
+        let mut r = format!("P4\n{} {}\n", self.width(), self.height()).into_bytes();
+        r.reserve(stride * self.height());
+        for y in 0..self.height() {
+            let mut row = vec![0; stride];
+            for x in 0..self.width() {
+                if self.get(x, y) {
+                    row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+            r.extend(row);
+        }
+        r
+    }
+}
+
+/**
+Conversion of grayscale to black and white; see [`Graymap::dither`]
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Dither {
+    /// Pixels darker than the given value (0-255) are black
+    Threshold(u8),
+
+    /// Floyd–Steinberg error diffusion
+    #[default]
+    FloydSteinberg,
+
+    /// Ordered dithering with a 4x4 Bayer matrix
+    Ordered,
+}
+
+/**
+Error returned by [`Graymap::from_netpbm`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NetpbmError {
+    /// Not a PBM, PGM or PPM image (`P1`-`P6`)
+    UnsupportedFormat,
+
+    /// Width, height or maximum value is missing or invalid
+    InvalidHeader,
+
+    /// Sample is not a number or exceeds the maximum value
+    InvalidSample,
+
+    /// Image ends before all pixels
+    Truncated,
+}
+
+impl core::fmt::Display for NetpbmError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            NetpbmError::UnsupportedFormat => "Not a PBM, PGM or PPM image!",
+            NetpbmError::InvalidHeader => "Invalid Netpbm header!",
+            NetpbmError::InvalidSample => "Invalid Netpbm sample!",
+            NetpbmError::Truncated => "Netpbm image is truncated!",
+        })
+    }
+}
+
+impl core::error::Error for NetpbmError {}
+
+/// 4x4 Bayer matrix
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Luminance (ITU-R BT.601) of scaled red, green and blue samples
+fn luminance(rgb: [Result<u8, NetpbmError>; 3]) -> Result<u8, NetpbmError> {
+    let [r, g, b] = rgb;
+    let sum = 299 * u32::from(r?) + 587 * u32::from(g?) + 114 * u32::from(b?);
+    Ok(u8::try_from(sum / 1000).unwrap())
+}
+
+/// Netpbm header and plain raster tokenizer
+struct Parser<'a> {
+    content: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Skip whitespace and comments
+    fn skip(&mut self) {
+        while let Some(b) = self.content.get(self.pos) {
+            match b {
+                b'#' => {
+                    while self.content.get(self.pos).is_some_and(|b| *b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Next whitespace separated token
+    fn token(&mut self) -> Option<&'a [u8]> {
+        self.skip();
+        let start = self.pos;
+        while self
+            .content
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#')
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| &self.content[start..self.pos])
+    }
+
+    /// Next decimal number
+    fn number(&mut self) -> Result<usize, NetpbmError> {
+        let token = self.token().ok_or(NetpbmError::Truncated)?;
+        core::str::from_utf8(token)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or(NetpbmError::InvalidSample)
+    }
+
+    /// Raw raster of the given number of bytes, if it does not overflow
+    fn raster(&mut self, len: Option<usize>) -> Result<&'a [u8], NetpbmError> {
+        let end = len
+            .and_then(|len| self.pos.checked_add(len))
+            .ok_or(NetpbmError::Truncated)?;
+        let r = self
+            .content
+            .get(self.pos..end)
+            .ok_or(NetpbmError::Truncated)?;
+        self.pos = end;
+        Ok(r)
+    }
+}
@@ -0,0 +1,177 @@
+use {
+    crate::{DecodeError, DecodeErrorKind, Style, Tables},
+    alloc::vec::Vec,
+};
+
+/**
+How decoding handles characters that are not Braille patterns, like carriage returns, a byte
+order mark, (non-breaking) spaces or Markdown fences around the cells
+
+Continuation markers (`\`) and newlines are always ignored. Braille patterns that are not valid
+cells of the style (see [`DecodeErrorKind::InvalidBcd`]) are errors with any policy.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Policy {
+    /// Fail at the first character that is not a Braille pattern
+    #[default]
+    Strict,
+
+    /// Drop characters that are not Braille patterns and count them
+    Skip,
+
+    /// Drop characters that are not Braille patterns and report each of them
+    Warn,
+}
+
+/**
+Result of [`decode_with_policy`]
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Decoded {
+    /// Decoded bytes
+    pub content: Vec<u8>,
+
+    /// Number of dropped characters
+    pub dropped: usize,
+
+    /// Dropped characters in order if the policy is [`Policy::Warn`]
+    pub warnings: Vec<DecodeError>,
+}
+
+/**
+Decode binary representation with the given policy for characters that are not Braille patterns
+
+```
+use bbd_lib::*;
+
+let content = "\u{feff}```\r\n⢄⠮⢦\\\r\n⢦⢾⢐ \r\n```\r\n";
+assert_eq!(
+    decode_with_policy(content, Style::Nlbb, Policy::Strict).unwrap_err().character,
+    '\u{feff}',
+);
+
+let decoded = decode_with_policy(content, Style::Nlbb, Policy::Skip).unwrap();
+assert_eq!(decoded.content, b"Hello\n");
+assert_eq!((decoded.dropped, decoded.warnings.len()), (12, 0));
+
+let decoded = decode_with_policy(content, Style::Nlbb, Policy::Warn).unwrap();
+assert_eq!(decoded.dropped, 12);
+let warning = decoded.warnings[5];
+assert_eq!((warning.character, warning.line, warning.column), ('\r', 2, 5));
+
+let error = decode_with_policy("⠀⣿", Style::Bcd, Policy::Skip).unwrap_err();
+assert_eq!((error.kind, error.column), (DecodeErrorKind::InvalidBcd, 2));
+```
+
+# Errors
+
+Returns an error for the first character that is not a Braille pattern if the policy is
+[`Policy::Strict`], or else for the first Braille pattern that is not a valid cell of the style
+*/
+pub fn decode_with_policy(
+    content: &str,
+    style: Style,
+    policy: Policy,
+) -> Result<Decoded, DecodeError> {
+    let mut r = Decoded {
+        content: Vec::with_capacity(content.len() / 3),
+        ..Decoded::default()
+    };
+    Tolerant::new(style, policy).decode(
+        content,
+        &mut r.content,
+        &mut r.dropped,
+        &mut r.warnings,
+    )?;
+    Ok(r)
+}
+
+/**
+Decoder that applies a [`Policy`] and tracks the position of the next character across calls
+*/
+#[derive(Clone)]
+pub(crate) struct Tolerant {
+    style: Style,
+    tables: Tables,
+    pub(crate) policy: Policy,
+
+    /// Zero-based index of the next character
+    index: usize,
+
+    /// One-based line of the next character
+    line: usize,
+
+    /// One-based column of the next character
+    column: usize,
+}
+
+impl Tolerant {
+    /// Create a decoder at the start of the content
+    pub(crate) fn new(style: Style, policy: Policy) -> Tolerant {
+        Tolerant {
+            style,
+            tables: style.tables(),
+            policy,
+            index: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /**
+    Decode complete characters, appending to `out` and counting (and, with [`Policy::Warn`],
+    recording) the dropped characters
+
+    On error, the bytes decoded before the failing character have been appended.
+    */
+    pub(crate) fn decode(
+        &mut self,
+        content: &str,
+        out: &mut Vec<u8>,
+        dropped: &mut usize,
+        warnings: &mut Vec<DecodeError>,
+    ) -> Result<(), DecodeError> {
+        let mut rest = content;
+        loop {
+            let Err(offset) = self.tables.decode_to(rest, out) else {
+                self.advance(rest);
+                return Ok(());
+            };
+            self.advance(&rest[..offset]);
+            let character = rest[offset..].chars().next().unwrap();
+            let error = DecodeError {
+                kind: self
+                    .style
+                    .try_decode_char(character)
+                    .err()
+                    .unwrap_or(DecodeErrorKind::NotBraille),
+                character,
+                index: self.index,
+                line: self.line,
+                column: self.column,
+            };
+            if self.policy == Policy::Strict || error.kind != DecodeErrorKind::NotBraille {
+                return Err(error);
+            }
+            *dropped += 1;
+            if self.policy == Policy::Warn {
+                warnings.push(error);
+            }
+            let (skipped, tail) = rest[offset..].split_at(character.len_utf8());
+            self.advance(skipped);
+            rest = tail;
+        }
+    }
+
+    /// Move the position past the given content
+    pub(crate) fn advance(&mut self, content: &str) {
+        let chars = |s: &str| s.bytes().filter(|b| b & 0xC0 != 0x80).count();
+        self.index += chars(content);
+        if let Some(i) = content.rfind('\n') {
+            self.line += content.bytes().filter(|b| *b == b'\n').count();
+            self.column = 1 + chars(&content[i + 1..]);
+        } else {
+            self.column += chars(content);
+        }
+    }
+}
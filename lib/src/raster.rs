@@ -0,0 +1,223 @@
+use {
+    crate::{DecodeError, encode_direct, try_decode_direct},
+    alloc::{vec, vec::Vec},
+    core::fmt::{self, Write},
+};
+
+/**
+Order of the pixels within a byte of a 1-bit-per-pixel bitmap; see [`Bitmap::from_bytes`]
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// Most significant bit first (left or top)
+    #[default]
+    Msb,
+
+    /// Least significant bit first (left or top)
+    Lsb,
+}
+
+/**
+Arrangement of the bytes of a 1-bit-per-pixel bitmap; see [`Bitmap::from_bytes`]
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Each byte is 8 horizontal pixels; each row starts at a new byte
+    #[default]
+    Rows,
+
+    /// Each byte is 8 vertical pixels; bytes run left to right in pages of 8 rows, like the
+    /// SSD1306 and similar display controllers
+    Pages,
+}
+
+/// Dot bits of the pixels of a cell by row and column: dots 1-3 and 4-6 are the upper 3 rows, dots
+/// 7 and 8 the bottom row
+const DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/**
+Monochrome bitmap shown as Braille cells of 2x4 pixels each
+
+Unlike [`encode`](crate::encode), which shows one byte per cell, each cell shows a 2x4 tile of the
+image, so a framebuffer or font ROM in a memory dump can be viewed as a picture. Each line of
+cells is 4 rows of pixels and ends with a newline; pixels outside the bitmap are off.
+
+```
+use bbd_lib::*;
+
+// 4x4 square outline
+let rows = Bitmap::from_bytes(&[0xf0, 0x90, 0x90, 0xf0], 4, BitOrder::Msb, Layout::Rows);
+assert_eq!((rows.width(), rows.height()), (4, 4));
+assert_eq!(rows.to_string(), "⣏⣹\n");
+
+// Same square in the page layout, least significant bit on top
+let pages = Bitmap::from_bytes(&[0x0f, 0x09, 0x09, 0x0f], 4, BitOrder::Lsb, Layout::Pages);
+assert_eq!((pages.width(), pages.height()), (4, 8));
+assert_eq!(pages.to_string(), "⣏⣹\n⠀⠀\n");
+```
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    /// Create a bitmap of the given size with all pixels off
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Bitmap {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    /**
+    Read bytes as a 1-bit-per-pixel bitmap of the given width in pixels
+
+    In the [`Layout::Rows`] layout, the height is the number of rows (of `width` pixels rounded up
+    to whole bytes) in the content. In the [`Layout::Pages`] layout, the height is 8 times the
+    number of pages (of `width` bytes). A short last row or page is filled with pixels that are
+    off.
+    */
+    #[must_use]
+    pub fn from_bytes(content: &[u8], width: usize, bit_order: BitOrder, layout: Layout) -> Bitmap {
+        if width == 0 {
+            return Bitmap::new(0, 0);
+        }
+        let bit = |b: u8, i: usize| match bit_order {
+            BitOrder::Msb => b & (0x80 >> i) != 0,
+            BitOrder::Lsb => b & (1 << i) != 0,
+        };
+        let mut r;
+        match layout {
+            Layout::Rows => {
+                let stride = width.div_ceil(8);
+                r = Bitmap::new(width, content.len().div_ceil(stride));
+                for (y, row) in content.chunks(stride).enumerate() {
+                    for x in 0..width.min(8 * row.len()) {
+                        r.set(x, y, bit(row[x / 8], x % 8));
+                    }
+                }
+            }
+            Layout::Pages => {
+                r = Bitmap::new(width, 8 * content.len().div_ceil(width));
+                for (page, bytes) in content.chunks(width).enumerate() {
+                    for (x, b) in bytes.iter().enumerate() {
+                        for i in 0..8 {
+                            r.set(x, 8 * page + i, bit(*b, i));
+                        }
+                    }
+                }
+            }
+        }
+        r
+    }
+
+    /**
+    Read Braille text as a bitmap; the inverse of the [`Display`](fmt::Display) implementation
+
+    Each line is 4 rows of pixels and each cell 2 columns; the width is that of the longest line.
+
+    ```
+    use bbd_lib::*;
+
+    let bitmap = Bitmap::from_braille("⣏⣹\n⠉\n").unwrap();
+    assert_eq!((bitmap.width(), bitmap.height()), (4, 8));
+    assert_eq!(bitmap.to_string(), "⣏⣹\n⠉⠀\n");
+
+    let error = Bitmap::from_braille("⣏⣹\n⠉x").unwrap_err();
+    assert_eq!((error.character, error.line, error.column), ('x', 2, 2));
+    ```
+
+    # Errors
+
+    Returns an error for the first character that is not a Braille pattern
+    */
+    pub fn from_braille(content: &str) -> Result<Bitmap, DecodeError> {
+        let lines = content.lines().collect::<Vec<&str>>();
+        let width = lines.iter().map(|line| line.chars().count()).max();
+        let mut r = Bitmap::new(2 * width.unwrap_or(0), 4 * lines.len());
+        let mut index = 0;
+        for (row, line) in lines.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                let dots = try_decode_direct(c).map_err(|kind| DecodeError {
+                    kind,
+                    character: c,
+                    index: index + column,
+                    line: row + 1,
+                    column: column + 1,
+                })?;
+                for (dy, dots_row) in DOTS.iter().enumerate() {
+                    for (dx, dot) in dots_row.iter().enumerate() {
+                        r.set(2 * column + dx, 4 * row + dy, dots & dot != 0);
+                    }
+                }
+            }
+            index += line.chars().count() + 1;
+        }
+        Ok(r)
+    }
+
+    /// Width in pixels
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixel at the given position; pixels outside the bitmap are off
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.pixels[y * self.width + x]
+    }
+
+    /**
+    Set the pixel at the given position
+
+    # Panics
+
+    Panics if the position is outside the bitmap
+    */
+    pub fn set(&mut self, x: usize, y: usize, on: bool) {
+        assert!(
+            x < self.width && y < self.height,
+            "Pixel {x},{y} is outside the {}x{} bitmap!",
+            self.width,
+            self.height,
+        );
+        self.pixels[y * self.width + x] = on;
+    }
+
+    /// Dot pattern of the cell with the given top left pixel
+    fn cell(&self, x: usize, y: usize) -> u8 {
+        let mut r = 0;
+        for (dy, row) in DOTS.iter().enumerate() {
+            for (dx, dot) in row.iter().enumerate() {
+                if self.get(x + dx, y + dy) {
+                    r |= dot;
+                }
+            }
+        }
+        r
+    }
+}
+
+impl fmt::Display for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in (0..self.height).step_by(4) {
+            for x in (0..self.width).step_by(2) {
+                f.write_char(encode_direct(self.cell(x, y)))?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
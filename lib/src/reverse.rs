@@ -0,0 +1,340 @@
+use {
+    crate::{DecodeError, Style},
+    alloc::{collections::BTreeMap, vec::Vec},
+};
+
+#[cfg(feature = "std")]
+use std::io::{Seek, SeekFrom, Write};
+
+/**
+Bytes of a dump line at their stated offset; see [`parse_dump`]
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DumpLine {
+    /// Offset of the first byte
+    pub offset: usize,
+
+    /// Bytes
+    pub content: Vec<u8>,
+}
+
+/**
+Parse an offset-annotated dump like the [`Hexdump`](crate::Hexdump) layout produces
+
+Each line may start with a hexadecimal offset followed by `:`; a line without an offset continues
+at the end of the previous line. The ASCII gutter (from the first `|`) is ignored. The bytes are
+taken from the binary representation column decoded with the given style if the line has one,
+otherwise from the hex column, so edits to the Braille cells win over a stale hex column. Plain
+binary representation with continuation markers (`\\`) is accepted too. Blank lines are skipped.
+For a little-endian [`Grouping`](crate::Grouping), the bytes within each group (separated by
+whitespace) are reversed back.
+
+```
+use bbd_lib::*;
+
+let dump = Hexdump { columns: 4, ..Hexdump::new(Style::Nlbb) }.format(b"Hello\n");
+assert_eq!(parse_dump(&dump, Style::Nlbb, false).unwrap(), vec![
+    DumpLine { offset: 0, content: b"Hell".to_vec() },
+    DumpLine { offset: 4, content: b"o\n".to_vec() },
+]);
+
+// Hex only, with a gap
+let lines = parse_dump("00000000: 4865\n00000010: 6c6c 6f0a\n", Style::Nlbb, false).unwrap();
+assert_eq!(lines[1], DumpLine { offset: 16, content: b"llo\n".to_vec() });
+
+assert_eq!(
+    parse_dump("00000000: 486", Style::Nlbb, false),
+    Err(ReverseError { line: 1, kind: ReverseErrorKind::InvalidHex }),
+);
+assert_eq!(
+    parse_dump("ffffffffffffffff: 4142", Style::Nlbb, false),
+    Err(ReverseError { line: 1, kind: ReverseErrorKind::OffsetOverflow }),
+);
+```
+
+# Errors
+
+Returns an error for the first line with an invalid offset, hex column or binary representation,
+or whose bytes would end past the largest offset
+*/
+pub fn parse_dump(
+    content: &str,
+    style: Style,
+    little_endian: bool,
+) -> Result<Vec<DumpLine>, ReverseError> {
+    let mut r = Vec::new();
+    let mut next = 0;
+    for (i, line) in content.lines().enumerate() {
+        let error = |kind| ReverseError { line: i + 1, kind };
+        let line = match line.find('|') {
+            Some(gutter) => &line[..gutter],
+            None => line,
+        };
+        let (offset, rest) = match line.split_once(':') {
+            Some((offset, rest)) => (
+                usize::from_str_radix(offset.trim(), 16)
+                    .map_err(|_| error(ReverseErrorKind::InvalidOffset))?,
+                rest,
+            ),
+            None => (next, line),
+        };
+
+        let (mut braille, mut hex) = (Vec::new(), Vec::new());
+        for token in rest.split_whitespace() {
+            let token = token.strip_suffix('\\').unwrap_or(token);
+            if token.chars().all(|c| c.is_ascii_hexdigit()) {
+                hex.push(token);
+            } else {
+                braille.push(token);
+            }
+        }
+        let mut bytes = Vec::new();
+        if braille.is_empty() {
+            for token in hex {
+                let start = bytes.len();
+                parse_hex(token, &mut bytes).ok_or(error(ReverseErrorKind::InvalidHex))?;
+                if little_endian {
+                    bytes[start..].reverse();
+                }
+            }
+        } else {
+            for token in braille {
+                let mut group = style.try_decode(token).map_err(|mut e| {
+                    e.line = i + 1;
+                    error(ReverseErrorKind::Decode(e))
+                })?;
+                if little_endian {
+                    group.reverse();
+                }
+                bytes.extend(group);
+            }
+        }
+        if bytes.is_empty() {
+            continue;
+        }
+
+        next = offset
+            .checked_add(bytes.len())
+            .ok_or(error(ReverseErrorKind::OffsetOverflow))?;
+        r.push(DumpLine {
+            offset,
+            content: bytes,
+        });
+    }
+    Ok(r)
+}
+
+/**
+Reverse an offset-annotated dump to bytes at their stated offsets with gaps zero-filled; see
+[`parse_dump`]
+
+Later lines overwrite earlier lines at the same offsets.
+
+```
+use bbd_lib::*;
+
+let dump = Hexdump::new(Style::Nrbt).format(b"Hello, world!\n");
+assert_eq!(reverse_dump(&dump, Style::Nrbt, false).unwrap(), b"Hello, world!\n");
+
+assert_eq!(reverse_dump("00000002: 4142", Style::Nlbb, false).unwrap(), b"\0\0AB");
+assert_eq!(reverse_dump("00000002: 4142", Style::Nlbb, true).unwrap(), b"\0\0BA");
+assert_eq!(reverse_dump("00000000: 414243\n00000001: 58", Style::Nlbb, false).unwrap(), b"AXC");
+
+let grouped = Hexdump { grouping: Some(Grouping::little_endian(4)), ..Hexdump::new(Style::Nlbb) };
+let dump = grouped.format(b"Hello, world!\n");
+assert_eq!(reverse_dump(&dump, Style::Nlbb, true).unwrap(), b"Hello, world!\n");
+```
+
+# Errors
+
+Returns an error for the first line that does not parse
+*/
+pub fn reverse_dump(
+    content: &str,
+    style: Style,
+    little_endian: bool,
+) -> Result<Vec<u8>, ReverseError> {
+    let mut r = Vec::new();
+    for (offset, bytes) in segments(parse_dump(content, style, little_endian)?) {
+        r.resize(offset, 0);
+        r.extend(bytes);
+    }
+    Ok(r)
+}
+
+/**
+Write the bytes of an offset-annotated dump to a writer at their stated offsets with gaps
+zero-filled; see [`parse_dump`]
+
+Unlike [`reverse_dump`], the output is streamed and the gaps are written a piece at a time, so a
+dump with a large offset does not need the whole output in memory. Returns the number of bytes
+written.
+
+```
+use bbd_lib::*;
+
+let mut out = vec![];
+assert_eq!(write_dump("00000002: 4142\n00000000: 43", Style::Nlbb, false, &mut out).unwrap(), 4);
+assert_eq!(out, b"C\0AB");
+
+let mut sink = std::io::sink();
+assert_eq!(write_dump("00100000: 41", Style::Nlbb, false, &mut sink).unwrap(), 0x100001);
+```
+
+# Errors
+
+Returns an error if a line does not parse (as [`std::io::ErrorKind::InvalidData`]) or writing fails
+*/
+#[cfg(feature = "std")]
+pub fn write_dump(
+    content: &str,
+    style: Style,
+    little_endian: bool,
+    out: &mut impl Write,
+) -> std::io::Result<usize> {
+    const ZEROS: [u8; 4096] = [0; 4096];
+    let lines = parse_dump(content, style, little_endian)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut written = 0;
+    for (offset, bytes) in segments(lines) {
+        while written < offset {
+            let n = (offset - written).min(ZEROS.len());
+            out.write_all(&ZEROS[..n])?;
+            written += n;
+        }
+        out.write_all(&bytes)?;
+        written += bytes.len();
+    }
+    Ok(written)
+}
+
+/**
+Patch the bytes of an offset-annotated dump into a seekable writer at their stated offsets; see
+[`parse_dump`]
+
+Gaps are seeked over, so the bytes outside the dump lines are left as they are. Returns the number
+of bytes written.
+
+```
+use {bbd_lib::*, std::io::Cursor};
+
+let mut file = Cursor::new(b"Hello, world!\n".to_vec());
+let mut dump = Hexdump::new(Style::Nlbb).format(b"Hello, world!\n");
+dump = dump.replace("⠿⢾⠗⢦⠦", &Style::Nlbb.encode(b"there", 0, 0));
+assert_eq!(patch_dump(&dump, Style::Nlbb, false, &mut file).unwrap(), 14);
+assert_eq!(file.into_inner(), b"Hello, there!\n");
+
+let mut file = Cursor::new(b"Hello, world!\n".to_vec());
+patch_dump("00000007: 4a", Style::Nlbb, false, &mut file).unwrap();
+assert_eq!(file.into_inner(), b"Hello, Jorld!\n");
+```
+
+# Errors
+
+Returns an error if a line does not parse (as [`std::io::ErrorKind::InvalidData`]) or seeking or
+writing fails
+*/
+#[cfg(feature = "std")]
+pub fn patch_dump(
+    content: &str,
+    style: Style,
+    little_endian: bool,
+    out: &mut (impl Write + Seek),
+) -> std::io::Result<usize> {
+    let lines = parse_dump(content, style, little_endian)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut written = 0;
+    for line in lines {
+        out.seek(SeekFrom::Start(line.offset as u64))?;
+        out.write_all(&line.content)?;
+        written += line.content.len();
+    }
+    Ok(written)
+}
+
+/// Resolve dump lines to non-overlapping runs of bytes keyed by offset, later lines overwriting
+/// earlier lines
+fn segments(lines: Vec<DumpLine>) -> BTreeMap<usize, Vec<u8>> {
+    let mut r: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+    for line in lines {
+        let (start, end) = (line.offset, line.offset + line.content.len());
+        let overlapping = r
+            .range(..end)
+            .rev()
+            .take_while(|(offset, bytes)| *offset + bytes.len() > start)
+            .map(|(offset, _)| *offset)
+            .collect::<Vec<_>>();
+        for offset in overlapping {
+            let mut bytes = r.remove(&offset).unwrap();
+            if offset + bytes.len() > end {
+                r.insert(end, bytes.split_off(end - offset));
+            }
+            if offset < start {
+                bytes.truncate(start - offset);
+                r.insert(offset, bytes);
+            }
+        }
+        r.insert(start, line.content);
+    }
+    r
+}
+
+/// Parse a hex token of whole bytes
+fn parse_hex(token: &str, bytes: &mut Vec<u8>) -> Option<()> {
+    if !token.len().is_multiple_of(2) {
+        return None;
+    }
+    for i in (0..token.len()).step_by(2) {
+        bytes.push(u8::from_str_radix(&token[i..i + 2], 16).ok()?);
+    }
+    Some(())
+}
+
+/**
+Error returned when parsing a dump
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReverseError {
+    /// One-based line number
+    pub line: usize,
+
+    /// Reason
+    pub kind: ReverseErrorKind,
+}
+
+impl core::fmt::Display for ReverseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.kind)
+    }
+}
+
+impl core::error::Error for ReverseError {}
+
+/**
+Reason a dump line does not parse
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReverseErrorKind {
+    /// Offset is not hexadecimal
+    InvalidOffset,
+
+    /// Hex column is not whole bytes
+    InvalidHex,
+
+    /// Bytes would end past the largest offset
+    OffsetOverflow,
+
+    /// Binary representation column does not decode
+    Decode(DecodeError),
+}
+
+impl core::fmt::Display for ReverseErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ReverseErrorKind::InvalidOffset => f.write_str("Invalid offset!"),
+            ReverseErrorKind::InvalidHex => f.write_str("Invalid hex column!"),
+            ReverseErrorKind::OffsetOverflow => f.write_str("Offset out of range!"),
+            ReverseErrorKind::Decode(e) => e.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use {
+    crate::{DecodeError, Policy, Style, Tables, policy::Tolerant},
+    std::io::{BufRead, Error, ErrorKind, Read, Result, Write},
+};
+
+/**
+Streaming encoder that writes the binary representation of all bytes written to it to an inner
+writer
+
+Bytes may be written in arbitrary chunks; the output, including the wrapping and continuation
+markers, is the same as [`encode`](crate::encode) would produce for the concatenated content.
+
+```
+use {bbd_lib::*, std::io::Write};
+
+let content = (0..=255).collect::<Vec<u8>>();
+
+let mut writer = BrailleWriter::new(vec![], Style::Nlbb, 10, 0);
+for chunk in content.chunks(7) {
+    writer.write_all(chunk).unwrap();
+}
+
+assert_eq!(
+    String::from_utf8(writer.into_inner()).unwrap(),
+    encode(&content, encode_nlbb, 10, 0),
+);
+```
+
+With the `bcd` style, writing a byte over 99 fails with an [`ErrorKind::InvalidInput`] error and
+writes nothing of that buffer.
+
+```
+use {bbd_lib::*, std::io::{ErrorKind, Write}};
+
+let mut writer = BrailleWriter::new(vec![], Style::Bcd, 0, 0);
+writer.write_all(&[1, 2, 3]).unwrap();
+
+let error = writer.write_all(&[4, 100]).unwrap_err();
+assert_eq!(error.kind(), ErrorKind::InvalidInput);
+assert_eq!(writer.into_inner(), encode(&[1, 2, 3], encode_bcd, 0, 0).into_bytes());
+```
+*/
+pub struct BrailleWriter<W: Write> {
+    inner: W,
+    tables: Tables,
+    columns: usize,
+    column: usize,
+    buffer: String,
+}
+
+impl<W: Write> BrailleWriter<W> {
+    /**
+    Create a new streaming encoder
+
+    The `columns` and `prev_content_length` arguments have the same meaning as for
+    [`encode`](crate::encode).
+    */
+    pub fn new(inner: W, style: Style, columns: usize, prev_content_length: usize) -> Self {
+        let column = if columns > 0 {
+            prev_content_length % columns
+        } else {
+            0
+        };
+        BrailleWriter {
+            inner,
+            tables: style.tables(),
+            columns,
+            column,
+            buffer: String::new(),
+        }
+    }
+
+    /// Reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Mutable reference to the inner writer
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consume the encoder and return the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for BrailleWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if let Some(decimal) = self.tables.invalid_bcd(buf) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid BCD value: {decimal}! Must be in range `0..=99`."),
+            ));
+        }
+        self.buffer.clear();
+        self.tables
+            .encode_to(buf, self.columns, &mut self.column, &mut self.buffer)
+            .unwrap();
+        self.inner.write_all(self.buffer.as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/**
+Streaming decoder that reads binary representation from an inner reader and yields the decoded
+bytes
+
+UTF-8 sequences and continuation markers may be split across reads of the inner reader.
+Characters that cannot be decoded produce an [`ErrorKind::InvalidData`] error wrapping a
+[`DecodeError`] once the bytes decoded before it have been read, unless they are dropped by the
+[`Policy`] given with [`with_policy`](BrailleReader::with_policy).
+
+```
+use {bbd_lib::*, std::io::Read};
+
+let content = (0..=255).collect::<Vec<u8>>();
+let encoded = encode(&content, encode_nlbb, 10, 0);
+
+// Feed the encoded content one byte at a time
+struct OneByte<'a>(&'a [u8]);
+impl Read for OneByte<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((b, rest)), Some(first)) => {
+                *first = *b;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+let mut reader = BrailleReader::new(OneByte(encoded.as_bytes()), Style::Nlbb);
+let mut decoded = vec![];
+reader.read_to_end(&mut decoded).unwrap();
+assert_eq!(decoded, content);
+
+let mut reader = BrailleReader::new("⢄⠮⢦\\\n⢦ ⢾⢐".as_bytes(), Style::Nlbb);
+let mut decoded = vec![];
+let error = reader.read_to_end(&mut decoded).unwrap_err();
+assert_eq!(decoded, b"Hell");
+let error = error.get_ref().unwrap().downcast_ref::<DecodeError>().unwrap();
+assert_eq!((error.character, error.line, error.column), (' ', 2, 2));
+
+let input = "⢄⠮⢦\\\r\n⢦ ⢾⢐\r\n".as_bytes();
+let mut reader = BrailleReader::new(input, Style::Nlbb).with_policy(Policy::Warn);
+let mut decoded = vec![];
+reader.read_to_end(&mut decoded).unwrap();
+assert_eq!(decoded, b"Hello\n");
+assert_eq!(reader.dropped(), 3);
+let warnings = reader.take_warnings();
+assert_eq!((warnings[1].character, warnings[1].line, warnings[1].column), (' ', 2, 2));
+```
+*/
+pub struct BrailleReader<R: Read> {
+    inner: R,
+    decoder: Tolerant,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    pos: usize,
+    dropped: usize,
+    warnings: Vec<DecodeError>,
+    error: Option<DecodeError>,
+    eof: bool,
+}
+
+impl<R: Read> BrailleReader<R> {
+    /// Create a new streaming decoder with the [`Policy::Strict`] policy
+    pub fn new(inner: R, style: Style) -> Self {
+        BrailleReader {
+            inner,
+            decoder: Tolerant::new(style, Policy::Strict),
+            input: vec![],
+            output: vec![],
+            pos: 0,
+            dropped: 0,
+            warnings: vec![],
+            error: None,
+            eof: false,
+        }
+    }
+
+    /// Set the policy for characters that are not Braille patterns
+    #[must_use]
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.decoder.policy = policy;
+        self
+    }
+
+    /// Number of characters dropped so far
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Take the characters dropped so far with the [`Policy::Warn`] policy
+    pub fn take_warnings(&mut self) -> Vec<DecodeError> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Mutable reference to the inner reader
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consume the decoder and return the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read the next chunk from the inner reader and decode its complete characters
+    fn decode_chunk(&mut self) -> Result<()> {
+        let mut chunk = [0; 8192];
+        let n = loop {
+            match self.inner.read(&mut chunk) {
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                r => break r?,
+            }
+        };
+        if n == 0 {
+            self.eof = true;
+            if !self.input.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "Incomplete UTF-8 sequence at end of input!",
+                ));
+            }
+            return Ok(());
+        }
+        self.input.extend_from_slice(&chunk[..n]);
+
+        let valid = match std::str::from_utf8(&self.input) {
+            Ok(s) => s.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Input is not valid UTF-8!",
+                ));
+            }
+        };
+        let mut input = std::mem::take(&mut self.input);
+        let content = std::str::from_utf8(&input[..valid]).unwrap();
+
+        self.output.clear();
+        self.pos = 0;
+        if let Err(error) = self.decoder.decode(
+            content,
+            &mut self.output,
+            &mut self.dropped,
+            &mut self.warnings,
+        ) {
+            self.error = Some(error);
+        }
+
+        input.drain(..valid);
+        self.input = input;
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for BrailleReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for BrailleReader<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        while self.pos >= self.output.len() {
+            if let Some(error) = self.error {
+                return Err(Error::new(ErrorKind::InvalidData, error));
+            }
+            if self.eof {
+                break;
+            }
+            self.decode_chunk()?;
+        }
+        Ok(&self.output[self.pos..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = (self.pos + amount).min(self.output.len());
+    }
+}
@@ -0,0 +1,196 @@
+use {
+    crate::{DecodeErrorKind, Style},
+    alloc::vec::Vec,
+};
+
+/**
+Check that binary representation is well-formed for the given style and wrap width without
+decoding it
+
+Checks that there are only Braille patterns that are valid cells of the style, continuation
+markers (`\`) and newlines, and that the lines are wrapped like [`Style::encode`] wraps them to the
+given number of columns ("bytes"): a line with a continuation marker ends at a multiple of the
+columns and a line without one does not reach it. Cells are counted across lines without
+continuation markers too, since `bbd` continues wrapping across files. With 0 columns, there must
+be no continuation markers.
+
+A run of consecutive characters that are not valid cells is reported once, at its first character.
+Returns all problems in order; an empty list means that the content is well-formed.
+
+```
+use bbd_lib::*;
+
+let dump = Style::Nlbb.encode(b"Hello, world!\n", 4, 0);
+assert_eq!(dump, "⢄⠮⢦⢦\\\n⢾⢢⠂⠿\\\n⢾⠗⢦⠦\\\n⠊⢐");
+assert!(verify(&dump, Style::Nlbb, 4).is_empty());
+
+let errors = verify("⢄⠮⢦\\\n⢦⢾x⢐\r\n", Style::Nlbb, 4);
+assert_eq!(errors, [
+    VerifyError { line: 1, column: 4, kind: VerifyErrorKind::Wrap { expected: 4, actual: 3 } },
+    VerifyError {
+        line: 2,
+        column: 3,
+        kind: VerifyErrorKind::Invalid('x', DecodeErrorKind::NotBraille),
+    },
+    VerifyError {
+        line: 2,
+        column: 5,
+        kind: VerifyErrorKind::Invalid('\r', DecodeErrorKind::NotBraille),
+    },
+]);
+
+// A run of invalid characters is one problem
+let errors = verify("`a.bin`:⢄⠮", Style::Nlbb, 0);
+assert_eq!(errors, [VerifyError {
+    line: 1,
+    column: 1,
+    kind: VerifyErrorKind::Invalid('`', DecodeErrorKind::NotBraille),
+}]);
+
+let errors = verify("⠀⣿\\⠀\n", Style::Bcd, 0);
+assert_eq!(errors, [
+    VerifyError {
+        line: 1,
+        column: 2,
+        kind: VerifyErrorKind::Invalid('⣿', DecodeErrorKind::InvalidBcd),
+    },
+    VerifyError { line: 1, column: 3, kind: VerifyErrorKind::Continuation },
+]);
+```
+*/
+#[must_use]
+pub fn verify(content: &str, style: Style, columns: usize) -> Vec<VerifyError> {
+    let mut r = Vec::new();
+    // Cells since the last wrap
+    let mut position = 0;
+    for (line, number) in content.split('\n').zip(1..) {
+        let error = |column, kind| VerifyError {
+            line: number,
+            column,
+            kind,
+        };
+        let (cells, wrapped) = match line.strip_suffix('\\') {
+            Some(cells) if columns > 0 => (cells, true),
+            _ => (line, false),
+        };
+        let mut count = 0;
+        // Previous character was not a valid cell
+        let mut invalid = false;
+        for (character, column) in cells.chars().zip(1..) {
+            if character == '\\' {
+                r.push(error(column, VerifyErrorKind::Continuation));
+                invalid = false;
+                continue;
+            }
+            let kind = match style.try_decode_char(character) {
+                Ok(_) => {
+                    count += 1;
+                    invalid = false;
+                    continue;
+                }
+                Err(DecodeErrorKind::NotBraille) => DecodeErrorKind::NotBraille,
+                Err(kind) => {
+                    count += 1;
+                    kind
+                }
+            };
+            if !invalid {
+                r.push(error(column, VerifyErrorKind::Invalid(character, kind)));
+            }
+            invalid = true;
+        }
+        if columns == 0 {
+            continue;
+        }
+        if wrapped {
+            let expected = columns - position;
+            if count != expected {
+                // Column of the continuation marker
+                r.push(error(
+                    cells.chars().count() + 1,
+                    VerifyErrorKind::Wrap {
+                        expected,
+                        actual: count,
+                    },
+                ));
+            }
+            position = 0;
+        } else if position + count >= columns {
+            let max = columns - position - 1;
+            r.push(error(
+                max + 1,
+                VerifyErrorKind::Overlong { max, actual: count },
+            ));
+            position = 0;
+        } else {
+            position += count;
+        }
+    }
+    r
+}
+
+/**
+Problem found by [`verify`]
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VerifyError {
+    /// One-based line number
+    pub line: usize,
+
+    /// One-based column in characters
+    pub column: usize,
+
+    /// Reason
+    pub kind: VerifyErrorKind,
+}
+
+impl core::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl core::error::Error for VerifyError {}
+
+/**
+Reason binary representation is not well-formed
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VerifyErrorKind {
+    /// Character is not a Braille pattern or not a valid cell of the style
+    Invalid(char, DecodeErrorKind),
+
+    /// Continuation marker is not at the end of a line or there is no wrapping
+    Continuation,
+
+    /// Line with a continuation marker does not have the expected number of cells
+    Wrap { expected: usize, actual: usize },
+
+    /// Line without a continuation marker has more than the maximum number of cells
+    Overlong { max: usize, actual: usize },
+}
+
+impl core::fmt::Display for VerifyErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            VerifyErrorKind::Invalid(character, kind) => write!(
+                f,
+                "Invalid character {character:?} (U+{:04X}): {kind}!",
+                u32::from(*character),
+            ),
+            VerifyErrorKind::Continuation => f.write_str("Unexpected continuation marker!"),
+            VerifyErrorKind::Wrap { expected, actual } => write!(
+                f,
+                "Wrapped line has {actual} cell(s) instead of {expected}!",
+            ),
+            VerifyErrorKind::Overlong { max, actual } => write!(
+                f,
+                "Line has {actual} cell(s) without a continuation marker; at most {max} allowed!",
+            ),
+        }
+    }
+}